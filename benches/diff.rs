@@ -0,0 +1,38 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+use dota2diff::{Config, PatchChange, parse_html_str, patch_diff};
+
+const FIXTURE: &str = include_str!("../html/7.32.html");
+
+fn bench_parse_html(c: &mut Criterion) {
+    let config = Config::default();
+    c.bench_function("parse_html_str 7.32", |b| {
+        b.iter(|| parse_html_str(black_box(FIXTURE), "7.32", &config))
+    });
+}
+
+// Several patches touching the same properties, so sorting and combining both have work to do
+fn synthetic_changes() -> Vec<PatchChange> {
+    let mut changes = vec![];
+    for version in ["7.32", "7.32b", "7.32c", "7.33"] {
+        for hero in 0..100 {
+            let tree_location = format!("Heroes > Hero {}", hero);
+            for property in 0..10 {
+                changes.push(PatchChange::parse_text(&format!("Stat {} increased by 2", property), tree_location.clone(), version));
+                changes.push(PatchChange::parse_text(&format!("Ability {} cooldown decreased from 20 to 18", property), tree_location.clone(), version));
+            }
+        }
+    }
+    changes
+}
+
+fn bench_patch_diff(c: &mut Criterion) {
+    let changes = synthetic_changes();
+    c.bench_function("patch_diff 8000 changes", |b| {
+        b.iter_batched(|| changes.clone(), |changes| patch_diff(black_box(changes)), criterion::BatchSize::LargeInput)
+    });
+}
+
+criterion_group!(benches, bench_parse_html, bench_patch_diff);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use crate::document::Source;
+
+const BUNDLED_ROSTER: &str = include_str!("../roster.json");
+
+pub struct Config {
+    pub skip_sections: Vec<String>,
+    // Old name -> new name for renamed heroes, items and abilities, so their changes combine across the rename
+    pub renames: BTreeMap<String, String>,
+    // Alias -> canonical name for h3 entity headers the wiki writes in more than one way
+    pub entity_aliases: BTreeMap<String, String>,
+    // Count the parsed versions on stderr while diffing, when stderr is a terminal
+    pub progress: bool,
+    // Absolute changes whose old and new numbers differ by at most this much are left out of the diff
+    pub unchanged_epsilon: f32,
+    // Keep absolute changes a later patch reverted, so they can be written as "touched (net unchanged)"
+    pub show_reverted: bool,
+    // Which markup the version files are in, detected from each file when not set
+    pub source: Option<Source>,
+    // Keep notes that could not be parsed in the order the patch lists them, instead of by their text
+    pub document_order: bool
+}
+
+impl Config {
+    // Reads renames from a JSON object like {"Old Name": "New Name"}
+    pub fn load_renames(&mut self, json: &str) -> Result<(), String> {
+        let renames: BTreeMap<String, String> = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        self.renames.extend(renames);
+        Ok(())
+    }
+
+    // Reads a roster from a JSON object like {"Canonical Name": ["Alias", "Other Alias"]}
+    pub fn load_roster(&mut self, json: &str) -> Result<(), String> {
+        let roster: BTreeMap<String, Vec<String>> = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        for (canonical, aliases) in roster {
+            for alias in aliases {
+                self.entity_aliases.insert(alias, canonical.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load_bundled_roster(&mut self) {
+        self.load_roster(BUNDLED_ROSTER).expect("Bundled roster.json is invalid")
+    }
+
+    // Section names are matched ignoring case and surrounding whitespace, as wiki exports vary in both
+    pub fn skips_section(&self, section: &str) -> bool {
+        let section = section.trim().to_lowercase();
+        self.skip_sections.iter().any(|skipped| skipped.trim().to_lowercase() == section)
+    }
+
+    pub fn canonical_entity<'a>(&'a self, name: &'a str) -> &'a str {
+        self.entity_aliases.get(name).map(|canonical| canonical.as_str()).unwrap_or(name)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            skip_sections: vec!["General".to_string(), "Additional Content".to_string()],
+            renames: BTreeMap::new(),
+            entity_aliases: BTreeMap::new(),
+            progress: false,
+            unchanged_epsilon: 0.0,
+            show_reverted: false,
+            source: None,
+            document_order: false
+        }
+    }
+}
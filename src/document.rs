@@ -0,0 +1,651 @@
+use regex::{Captures, Regex};
+use std::collections::{BTreeMap, BTreeSet};
+use scraper::{Html, Selector, ElementRef};
+
+use crate::config::Config;
+use crate::model::{PatchChange, TreePath};
+
+const FACET_GROUP: &str = "Facets";
+const INNATE_GROUP: &str = "Innate";
+// h3 headers that group changes under the current hero instead of starting a new one
+const SUB_SECTIONS: [&str; 2] = [FACET_GROUP, INNATE_GROUP];
+// "(with Aghanim's Scepter)" notes, so scepter and shard values get their own property
+const AGHANIM_STR: &str = r"\(with Aghanim.s (Scepter|Shard)\)";
+// Neutral item tiers, an h3 level above the items themselves
+const TIER_STR: &str = r"^Tier \d+$";
+// Parenthesized after a value to say which attack type it is for, so they are not notes
+const ATTACK_TYPES: [&str; 3] = ["melee", "ranged", "melee/ranged"];
+// Values for both attack types in one bullet, "20 (melee) / 10 (ranged)"
+const ATTACK_PAIR_STR: &str = r"(?i)(\S+) \(melee\) ?/ ?(\S+) \(ranged\)";
+// Ends a bullet whose two-level values are melee first and ranged second, "from 20/10 to 25/15 (melee/ranged)"
+const BOTH_ATTACK_TYPES: &str = " (melee/ranged)";
+const ATTACK_TYPE_STR: &str = r"(?i) \((melee|ranged)\)";
+// The element holding the notes of a Steam news post
+const STEAM_BODY: &str = ".EventDetailsBody";
+// Wiki markup around the notes, like the "[edit]" links, whose text is not part of any change
+const BOILERPLATE_CLASSES: [&str; 3] = ["mw-editsection", "mw-empty-elt", "noprint"];
+
+pub fn parse_html_str(html: &str, version: &str, config: &Config) -> Vec<PatchChange> {
+    let document = Html::parse_document(html);
+    parse_patch_document(document, version, config)
+}
+
+pub fn parse_patch_document(document: Html, version: &str, config: &Config) -> Vec<PatchChange> {
+    let source = config.source.unwrap_or_else(|| Source::detect(&document));
+    source.parser().parse(&document, version, config)
+}
+
+// Every section header in the document with the entity headers under it, including sections the parser skips
+pub fn parse_sections(html: &str) -> BTreeMap<String, BTreeSet<String>> {
+    let document = Html::parse_document(html);
+    Source::detect(&document).parser().sections(&document)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    Wiki,
+    Steam
+}
+
+impl Source {
+    // Steam news posts keep their notes in an .EventDetailsBody, anything else is read as a wiki page
+    pub fn detect(document: &Html) -> Source {
+        Source::find(document).unwrap_or(Source::Wiki)
+    }
+
+    // The source whose notes element is in the document, None when neither is
+    pub fn find(document: &Html) -> Option<Source> {
+        let wiki_body = Selector::parse(".mw-parser-output").unwrap();
+        let steam_body = Selector::parse(STEAM_BODY).unwrap();
+        if document.select(&wiki_body).next().is_some() {
+            Some(Source::Wiki)
+        } else if document.select(&steam_body).next().is_some() {
+            Some(Source::Steam)
+        } else {
+            None
+        }
+    }
+
+    pub fn parser(self) -> &'static dyn DocumentParser {
+        match self {
+            Source::Wiki => &WikiParser,
+            Source::Steam => &SteamParser
+        }
+    }
+}
+
+// The parts of a patch document the parser cares about, in document order
+pub enum Block<'a> {
+    Section(String),
+    Entity(String),
+    List(ElementRef<'a>)
+}
+
+pub trait DocumentParser {
+    fn blocks<'a>(&self, document: &'a Html) -> Vec<Block<'a>>;
+
+    fn parse(&self, document: &Html, version: &str, config: &Config) -> Vec<PatchChange> {
+        let tier_header = Regex::new(TIER_STR).unwrap();
+
+        let mut current_h2 = "".to_string();
+        let mut current_tier: Option<String> = None;
+        let mut current_h3 = "".to_string();
+        let mut current_groups: Vec<String> = vec![];
+
+        let mut patch_changes: Vec<PatchChange> = vec![];
+
+        for block in self.blocks(document) {
+            match block {
+                Block::Section(header) => {
+                    current_h2 = header;
+                    current_tier = None;
+                    current_groups = vec![];
+                },
+                Block::Entity(header) => {
+                    // "Facets" and "Innate" sub-sections belong to the hero above them
+                    if SUB_SECTIONS.contains(&header.as_str()) {
+                        current_groups = vec![header];
+                    } else if tier_header.is_match(&header) {
+                        current_tier = Some(header);
+                        current_h3 = "".to_string();
+                        current_groups = vec![];
+                    } else {
+                        current_h3 = config.canonical_entity(&header).to_string();
+                        current_groups = vec![];
+                    }
+                },
+                Block::List(element) => {
+                    let mut tree_loc = TreePath::default();
+                    tree_loc.push(&current_h2);
+                    if let Some(tier) = &current_tier {
+                        tree_loc.push(tier);
+                    }
+                    tree_loc.push(&current_h3);
+                    let mut ul_changes = parse_ul_element(element, tree_loc, version, &current_groups);
+                    if !config.renames.is_empty() {
+                        ul_changes.iter_mut().for_each(|change| change.rename(&config.renames));
+                    }
+                    // Skipped sections are still read for changes that move to a section of their own, like the Shard cost
+                    ul_changes.retain(|change| !config.skips_section(&change.property().segments()[0]));
+                    patch_changes.append(&mut ul_changes);
+                }
+            }
+        }
+        patch_changes
+    }
+
+    fn sections(&self, document: &Html) -> BTreeMap<String, BTreeSet<String>> {
+        let mut sections: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut current_h2: Option<String> = None;
+        for block in self.blocks(document) {
+            match block {
+                Block::Section(header) => {
+                    sections.entry(header.to_string()).or_default();
+                    current_h2 = Some(header);
+                },
+                Block::Entity(header) => {
+                    if let Some(h2) = &current_h2 {
+                        sections.entry(h2.to_string()).or_default().insert(header);
+                    }
+                },
+                Block::List(_) => {}
+            }
+        }
+        sections
+    }
+}
+
+// Liquipedia-style wiki pages, with h2, h3 and ul elements directly under .mw-parser-output
+pub struct WikiParser;
+
+impl DocumentParser for WikiParser {
+    fn blocks<'a>(&self, document: &'a Html) -> Vec<Block<'a>> {
+        let primary_div = Selector::parse(".mw-parser-output > *").unwrap();
+        document.select(&primary_div)
+            // Headers without any text (e.g. only an image) keep the previous header
+            .filter_map(|element| match element.value().name() {
+                "h2" => header_text(element).map(Block::Section),
+                "h3" => header_text(element).map(Block::Entity),
+                "ul" => Some(Block::List(element)),
+                _ => None
+            })
+            .collect()
+    }
+}
+
+// Steam news posts, where bbcode headers become .bb_h2 and .bb_h3 divs and lists become ul.bb_ul
+pub struct SteamParser;
+
+impl DocumentParser for SteamParser {
+    fn blocks<'a>(&self, document: &'a Html) -> Vec<Block<'a>> {
+        let selector = Selector::parse(&format!("{0} .bb_h2, {0} .bb_h3, {0} h2, {0} h3, {0} ul", STEAM_BODY)).unwrap();
+        document.select(&selector)
+            .filter_map(|element| {
+                let classes: Vec<&str> = element.value().classes().collect();
+                match element.value().name() {
+                    // Nested lists are read along with the list they are in
+                    "ul" if element.ancestors().any(|ancestor| ElementRef::wrap(ancestor).is_some_and(|parent| parent.value().name() == "li")) => None,
+                    "ul" => Some(Block::List(element)),
+                    "h2" => header_text(element).map(Block::Section),
+                    "h3" => header_text(element).map(Block::Entity),
+                    _ if classes.contains(&"bb_h2") => header_text(element).map(Block::Section),
+                    _ if classes.contains(&"bb_h3") => header_text(element).map(Block::Entity),
+                    _ => None
+                }
+            })
+            .collect()
+    }
+}
+
+fn header_text(header: ElementRef) -> Option<String> {
+    let headline_selector = Selector::parse(".mw-headline").unwrap();
+    let headline = header.select(&headline_selector).next().unwrap_or(header);
+    headline.text().map(str::trim).find(|text| !text.is_empty()).map(str::to_string)
+}
+
+// Trims a text fragment and drops trailing " (...)" notes, keeping attack types after a value like "6 (melee)"
+fn normalize_line(text: &str) -> &str {
+    let mut line = text.trim();
+    while let Some(start) = line.strip_suffix(')').and_then(|rest| rest.rfind(" (")) {
+        let note = &line[start + 2..line.len() - 1];
+        let after_value = line[..start].rsplit(' ').next().is_some_and(|word| word.chars().any(|c| c.is_ascii_digit()));
+        if after_value && ATTACK_TYPES.contains(&note.to_lowercase().as_str()) {
+            break
+        }
+        line = line[..start].trim_end();
+    }
+    line
+}
+
+// Collects the <b> headers of every <li> (within the ul) enclosing the given <b>, outermost first
+fn b_group_path(b: ElementRef, ul: ElementRef) -> Vec<String> {
+    let b_selector = Selector::parse("b").unwrap();
+    let mut path = vec![normalize_line(b.text().next().unwrap_or("")).to_string()];
+
+    for ancestor in b.ancestors() {
+        if ancestor.id() == ul.id() {
+            break;
+        }
+        if let Some(li) = ElementRef::wrap(ancestor) {
+            if li.value().name() != "li" {
+                continue;
+            }
+            if let Some(group_b) = li.select(&b_selector).next() {
+                if group_b.id() != b.id() {
+                    path.insert(0, normalize_line(group_b.text().next().unwrap_or("")).to_string());
+                }
+            }
+        }
+    }
+    path
+}
+
+fn group_label(groups: &[String]) -> Vec<String> {
+    match groups.split_first() {
+        Some((first, facet)) if first == FACET_GROUP && !facet.is_empty() => {
+            let mut label = vec![format!("Facet: {}", facet[0])];
+            label.extend_from_slice(&facet[1..]);
+            label
+        },
+        _ => groups.to_vec()
+    }
+}
+
+// The text of an <li> without the lists nested in it, with inline markup like <b> or <a> joined back in
+fn li_text(li: ElementRef) -> String {
+    fn collect(element: ElementRef, text: &mut String) {
+        for child in element.children() {
+            if let Some(fragment) = child.value().as_text() {
+                text.push_str(fragment);
+            } else if let Some(child_element) = ElementRef::wrap(child) {
+                if !matches!(child_element.value().name(), "ul" | "ol") && !is_boilerplate(child_element) {
+                    collect(child_element, text);
+                }
+            }
+        }
+    }
+    let mut text = String::new();
+    collect(li, &mut text);
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn is_boilerplate(element: ElementRef) -> bool {
+    element.value().classes().any(|class| BOILERPLATE_CLASSES.contains(&class))
+}
+
+// The <b> of an <li> that holds nothing else, which makes the <li> the header of a group
+fn group_b<'a>(li: ElementRef<'a>, line: &str) -> Option<ElementRef<'a>> {
+    let b_selector = Selector::parse("b").unwrap();
+    let b = li.select(&b_selector).next()?;
+    let in_li = b.ancestors().filter_map(ElementRef::wrap).find(|ancestor| ancestor.value().name() == "li")?.id() == li.id();
+    (in_li && normalize_line(&b.text().collect::<String>()) == line).then_some(b)
+}
+
+pub fn parse_ul_element(ul: ElementRef, tree_loc: TreePath, version: &str, parent_groups: &[String]) -> Vec<PatchChange> {
+    let mut ul_changes: Vec<PatchChange> = vec![];
+    let li_selector = Selector::parse("li").unwrap();
+    let mut current_b = group_label(parent_groups);
+
+    let aghanim_match = Regex::new(AGHANIM_STR).unwrap();
+
+    for li in ul.select(&li_selector) {
+        let raw_line = li_text(li);
+        let change_line = normalize_line(&raw_line);
+        if change_line.is_empty() {
+            continue
+        }
+
+        if let Some(b_value) = group_b(li, change_line) {
+            let mut groups = parent_groups.to_vec();
+            groups.append(&mut b_group_path(b_value, ul));
+            current_b = group_label(&groups);
+            continue;
+        }
+
+        let mut tree_location = tree_loc.clone();
+        for group in &current_b {
+            tree_location.push(group);
+        }
+        if let Some(capture_groups) = aghanim_match.captures(&raw_line) {
+            tree_location.push(&capture_groups[1]);
+        }
+
+        for (attack_type, line) in split_attack_types(change_line) {
+            let mut attack_location = tree_location.clone();
+            if let Some(attack_type) = attack_type {
+                attack_location.push(attack_type);
+            }
+            ul_changes.push(PatchChange::parse_text(&line, attack_location, version));
+        }
+    }
+
+    ul_changes
+}
+
+// One line per attack type the values are given for, with the attack type notes taken out.
+// Lines without attack types are kept whole
+fn split_attack_types(line: &str) -> Vec<(Option<&'static str>, String)> {
+    let attack_pair = Regex::new(ATTACK_PAIR_STR).unwrap();
+    if attack_pair.is_match(line) {
+        return [("Melee", 1), ("Ranged", 2)].into_iter()
+            .map(|(attack_type, side)| {
+                let split = attack_pair.replace_all(line, |capture_groups: &Captures| capture_groups[side].to_string());
+                (Some(attack_type), split.to_string())
+            })
+            .collect()
+    }
+    let suffix_start = line.len().saturating_sub(BOTH_ATTACK_TYPES.len());
+    let has_both_types = line.get(suffix_start..).is_some_and(|suffix| suffix.eq_ignore_ascii_case(BOTH_ATTACK_TYPES));
+    if let Some(values) = has_both_types.then(|| &line[..suffix_start]) {
+        return [("Melee", 0), ("Ranged", 1)].into_iter()
+            .map(|(attack_type, side)| {
+                let split: Vec<&str> = values.split(' ')
+                    .map(|word| match word.split('/').collect::<Vec<&str>>().as_slice() {
+                        [melee, ranged] => if side == 0 { *melee } else { *ranged },
+                        _ => word
+                    })
+                    .collect();
+                (Some(attack_type), split.join(" "))
+            })
+            .collect()
+    }
+
+    let attack_note = Regex::new(ATTACK_TYPE_STR).unwrap();
+    let attack_types: BTreeSet<String> = attack_note.captures_iter(line).map(|capture_groups| capture_groups[1].to_lowercase()).collect();
+    match attack_types.iter().next() {
+        Some(attack_type) if attack_types.len() == 1 => {
+            let attack_type = if attack_type == "melee" { "Melee" } else { "Ranged" };
+            vec![(Some(attack_type), attack_note.replace_all(line, "").to_string())]
+        },
+        _ => vec![(None, line.to_string())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::{Html, Selector};
+    use crate::{ChangeData, Config, patch_diff};
+    use std::collections::BTreeSet;
+    use crate::document::{Source, normalize_line, parse_html_str, parse_sections, parse_ul_element, parse_patch_document};
+    use crate::model::TreePath;
+
+    #[test]
+    fn parse_html_str_works() {
+        let html = "<html><body><div class=\"mw-parser-output\">\
+            <h2>General</h2><h3>Gameplay</h3><ul><li>Something random</li></ul>\
+            <h2>Items</h2><h3>Blade Mail</h3><ul><li>Duration increased from 4.5s to 5.5s</li></ul>\
+            </div></body></html>";
+        let result = parse_html_str(html, "7.32", &Config::default());
+
+        assert_eq!(1, result.len());
+        assert_eq!("Items > Blade Mail > Duration increased from 4.5s to 5.5s".to_string(), result[0].write_text())
+    }
+
+    #[test]
+    fn b_group_with_note_and_whitespace_works() {
+        let fragment = Html::parse_fragment(
+            "<ul><li><b>Thundergod's Wrath (Aghanim's Scepter) </b><ul>\
+            <li>Cooldown decreased from 90 to 80</li></ul></li></ul>"
+        );
+        let ul_selector = Selector::parse("ul").unwrap();
+        let ul = fragment.select(&ul_selector).next().unwrap();
+        let result = parse_ul_element(ul, TreePath::from("Heroes > Zeus"), "7.32", &[]);
+
+        assert_eq!(1, result.len());
+        assert_eq!(
+            "Heroes > Zeus > Thundergod's Wrath > Cooldown decreased from 90 to 80".to_string(),
+            result[0].write_text()
+        )
+    }
+
+    #[test]
+    fn inline_markup_stays_one_change() {
+        let fragment = Html::parse_fragment(
+            "<ul><li>Now deals <b>bonus</b> damage to <a href=\"/Roshan\">Roshan</a>\n and his minions</li>\
+            <li><b>Arc Lightning</b><ul><li>Bounces <i>twice</i> off illusions</li></ul></li></ul>"
+        );
+        let ul_selector = Selector::parse("ul").unwrap();
+        let ul = fragment.select(&ul_selector).next().unwrap();
+        let result = parse_ul_element(ul, TreePath::from("Heroes > Zeus"), "7.32", &[]);
+
+        assert_eq!(2, result.len());
+        assert_eq!(&ChangeData::OtherChange("Now deals bonus damage to Roshan and his minions".to_string()), result[0].data());
+        assert_eq!("Heroes > Zeus > Arc Lightning > Bounces twice off illusions".to_string(), result[1].write_text())
+    }
+
+    #[test]
+    fn edit_section_text_is_skipped() {
+        let fragment = Html::parse_fragment(
+            "<ul><li>Base armor increased by 1<span class=\"mw-editsection\"><span>[</span><a href=\"/edit\">edit</a><span>]</span></span></li>\
+            <li class=\"mw-empty-elt\"></li>\
+            <li><span class=\"mw-editsection\">[<a href=\"/edit\">edit</a>]</span></li></ul>"
+        );
+        let ul_selector = Selector::parse("ul").unwrap();
+        let ul = fragment.select(&ul_selector).next().unwrap();
+        let result = parse_ul_element(ul, TreePath::from("Heroes > Zeus"), "7.32", &[]);
+
+        assert_eq!(vec!["Heroes > Zeus > Base armor increased by 1".to_string()],
+            result.iter().map(|change| change.write_text()).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn normalize_line_keeps_attack_types() {
+        assert_eq!("Damage increased from 100 to 150", normalize_line(" Damage increased from 100 to 150 (with Aghanim's Scepter) "));
+        assert_eq!("Blink Dagger", normalize_line("Blink Dagger (Reworked) (see below)"));
+        assert_eq!("Berserker's Rage", normalize_line("Berserker's Rage (Melee)"));
+        assert_eq!("Cooldown (with Shard) increased from 5 to 6", normalize_line("Cooldown (with Shard) increased from 5 to 6"));
+        assert_eq!("Attack damage increased from 5 (melee) to 6 (melee)", normalize_line("Attack damage increased from 5 (melee) to 6 (melee)"));
+        assert_eq!("Damage increased from 20/10 to 25/15 (melee/ranged)", normalize_line("Damage increased from 20/10 to 25/15 (melee/ranged)"))
+    }
+
+    #[test]
+    fn melee_qualifier_survives() {
+        let html = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Morphling</h3>\
+            <ul><li>Attack damage increased from 5 (melee) to 6 (melee)</li></ul></div>";
+        let result = parse_html_str(html, "7.32", &Config::default());
+
+        assert_eq!(1, result.len());
+        assert_eq!(&ChangeData::AbsoluteChange("5".to_string(), "6".to_string()), result[0].data());
+        assert_eq!("Heroes > Morphling > Melee > Attack damage increased from 5 to 6".to_string(), result[0].write_text())
+    }
+
+    #[test]
+    fn melee_and_ranged_values_split() {
+        let html = "<div class=\"mw-parser-output\"><h2>Items</h2><h3>Quelling Blade</h3>\
+            <ul><li>Damage bonus increased from 8 (melee) / 4 (ranged) to 10 (melee) / 5 (ranged)</li>\
+            <li>Bash chance decreased from 25%/10% to 20%/8% (melee/ranged)</li></ul></div>";
+        let result: Vec<String> = parse_html_str(html, "7.32", &Config::default()).iter().map(|change| change.write_text()).collect();
+
+        assert_eq!(vec![
+            "Items > Quelling Blade > Melee > Damage bonus increased from 8 to 10".to_string(),
+            "Items > Quelling Blade > Ranged > Damage bonus increased from 4 to 5".to_string(),
+            "Items > Quelling Blade > Melee > Bash chance decreased from 25% to 20%".to_string(),
+            "Items > Quelling Blade > Ranged > Bash chance decreased from 10% to 8%".to_string()
+        ], result)
+    }
+
+    #[test]
+    fn facet_b_group_works() {
+        let document = Html::parse_document(
+            "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Invoker</h3>\
+            <ul><li><b>Facets</b><ul><li><b>Quas Wex</b><ul>\
+            <li>Cooldown decreased from 10 to 8</li></ul></li></ul></li>\
+            <li><b>Cold Snap</b><ul><li>Cooldown decreased from 20 to 18</li></ul></li></ul></div>"
+        );
+        let result = parse_patch_document(document, "7.36", &Config::default());
+
+        assert_eq!(2, result.len());
+        assert_eq!(
+            "Heroes > Invoker > Facet: Quas Wex > Cooldown decreased from 10 to 8".to_string(),
+            result[0].write_text()
+        );
+        assert_eq!(
+            "Heroes > Invoker > Cold Snap > Cooldown decreased from 20 to 18".to_string(),
+            result[1].write_text()
+        )
+    }
+
+    #[test]
+    fn facet_h3_section_works() {
+        let document = Html::parse_document(
+            "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Invoker</h3>\
+            <ul><li>Base armor increased by 1</li></ul><h3>Facets</h3>\
+            <ul><li><b>Quas Wex</b><ul><li>Cooldown decreased from 10 to 8</li></ul></li></ul></div>"
+        );
+        let result = parse_patch_document(document, "7.36", &Config::default());
+
+        assert_eq!(2, result.len());
+        assert_eq!("Heroes > Invoker > Base armor increased by 1".to_string(), result[0].write_text());
+        assert_eq!(
+            "Heroes > Invoker > Facet: Quas Wex > Cooldown decreased from 10 to 8".to_string(),
+            result[1].write_text()
+        )
+    }
+
+    #[test]
+    fn innate_h3_section_works() {
+        let document = Html::parse_document(
+            "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Muerta</h3>\
+            <ul><li>Base armor increased by 1</li></ul><h3>Innate</h3>\
+            <ul><li>Gunslinger proc chance increased from 50% to 55%</li></ul></div>"
+        );
+        let result = parse_patch_document(document, "7.35", &Config::default());
+
+        assert_eq!(2, result.len());
+        assert_eq!(
+            "Heroes > Muerta > Innate > Gunslinger proc chance increased from 50% to 55%".to_string(),
+            result[1].write_text()
+        )
+    }
+
+    #[test]
+    fn neutral_item_tier_works() {
+        let html = "<div class=\"mw-parser-output\"><h2>Neutral Items</h2><h3>Tier 3</h3>\
+            <h3>Pirate Hat</h3><ul><li>Attack speed increased from 25 to 30</li></ul>\
+            <h2>Items</h2><h3>Blade Mail</h3><ul><li>Armor increased from 4 to 5</li></ul></div>";
+        let result = parse_html_str(html, "7.32", &Config::default());
+
+        assert_eq!("Neutral Items > Tier 3 > Pirate Hat > Attack speed increased from 25 to 30".to_string(), result[0].write_text());
+        assert_eq!("Items > Blade Mail > Armor increased from 4 to 5".to_string(), result[1].write_text())
+    }
+
+    #[test]
+    fn renamed_ability_combines() {
+        let mut config = Config::default();
+        config.load_renames(r#"{"Dragon Slave": "Dragon Breath"}"#).unwrap();
+        let html_32 = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Lina</h3>\
+            <ul><li><b>Dragon Slave</b><ul><li>Damage increased from 100 to 110</li></ul></li></ul></div>";
+        let html_32b = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Lina</h3>\
+            <ul><li><b>Dragon Breath</b><ul><li>Damage increased from 110 to 120</li></ul></li></ul></div>";
+        let mut changes = parse_html_str(html_32, "7.32", &config);
+        changes.extend(parse_html_str(html_32b, "7.32b", &config));
+        let result = patch_diff(changes);
+
+        assert_eq!(1, result.len());
+        assert_eq!("Heroes > Lina > Dragon Breath > Damage increased from 100 to 120".to_string(), result[0].write_text())
+    }
+
+    #[test]
+    fn roster_alias_uses_canonical_name() {
+        let mut config = Config::default();
+        config.load_bundled_roster();
+        let html = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Furion</h3>\
+            <ul><li>Base armor increased by 1</li></ul><h3>Nature's Prophet</h3>\
+            <ul><li>Base damage increased by 2</li></ul></div>";
+        let result: Vec<String> = parse_html_str(html, "7.32", &config).iter().map(|change| change.write_text()).collect();
+
+        assert_eq!(vec![
+            "Heroes > Nature's Prophet > Base armor increased by 1".to_string(),
+            "Heroes > Nature's Prophet > Base damage increased by 2".to_string()
+        ], result)
+    }
+
+    #[test]
+    fn aghanim_condition_gets_own_property() {
+        let html = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Lina</h3>\
+            <ul><li>Damage increased from 100 to 120</li>\
+            <li>Damage increased from 100 to 150 (with Aghanim's Scepter)</li></ul></div>";
+        let result = patch_diff(parse_html_str(html, "7.32", &Config::default()));
+
+        assert_eq!(2, result.len());
+        assert_eq!("Heroes > Lina > Damage increased from 100 to 120".to_string(), result[0].write_text());
+        assert_eq!("Heroes > Lina > Scepter > Damage increased from 100 to 150".to_string(), result[1].write_text())
+    }
+
+    #[test]
+    fn parse_sections_works() {
+        let html = "<div class=\"mw-parser-output\"><h2>General</h2><ul><li>Something random</li></ul>\
+            <h2>Heroes</h2><h3>Zeus</h3><ul><li>Base armor increased by 1</li></ul><h3>Axe</h3>\
+            <h2>Neutral Items</h2><h3>Tier 3</h3><h3>Pirate Hat</h3><h3>Axe</h3></div>";
+        let result = parse_sections(html);
+
+        let sections: Vec<(&str, Vec<&str>)> = result.iter()
+            .map(|(h2, h3s)| (h2.as_str(), h3s.iter().map(|h3| h3.as_str()).collect()))
+            .collect();
+        assert_eq!(vec![
+            ("General", vec![]),
+            ("Heroes", vec!["Axe", "Zeus"]),
+            ("Neutral Items", vec!["Axe", "Pirate Hat", "Tier 3"])
+        ], sections)
+    }
+
+    #[test]
+    fn text_less_header_is_skipped() {
+        let html = "<div class=\"mw-parser-output\"><h2>Items</h2><h3>Blade Mail</h3>\
+            <ul><li>Duration increased from 4.5s to 5.5s</li></ul>\
+            <h3><span class=\"mw-headline\"><a href=\"/dota2/Armor\"><img alt=\"\"></a></span>\
+            <span class=\"mw-editsection\">[<a>edit</a>]</span></h3>\
+            <ul><li>Armor increased from 4 to 5</li></ul></div>";
+        let result = parse_html_str(html, "7.32", &Config::default());
+
+        assert_eq!(2, result.len());
+        assert_eq!("Items > Blade Mail > Armor increased from 4 to 5".to_string(), result[1].write_text())
+    }
+
+    #[test]
+    fn skipped_section_ignores_case() {
+        let html = "<div class=\"mw-parser-output\">\
+            <h2>GENERAL</h2><h3>Gameplay</h3><ul><li>Something random</li></ul>\
+            <h2> additional content </h2><h3>Arcana</h3><ul><li>Something else</li></ul>\
+            <h2>Items</h2><h3>Blade Mail</h3><ul><li>Duration increased from 4.5s to 5.5s</li></ul></div>";
+        let result = parse_html_str(html, "7.32", &Config::default());
+
+        assert_eq!(1, result.len());
+        assert_eq!("Items > Blade Mail > Duration increased from 4.5s to 5.5s".to_string(), result[0].write_text())
+    }
+
+    #[test]
+    fn shard_cost_is_kept_from_skipped_section() {
+        let html = "<div class=\"mw-parser-output\"><h2>General</h2><h3>Gameplay</h3>\
+            <ul><li>Something random</li><li>Aghanim's Shard cost increased from 1400 to 1500</li></ul></div>";
+        let result = parse_html_str(html, "7.32", &Config::default());
+
+        assert_eq!(1, result.len());
+        assert_eq!("Items > Aghanim's Shard > Cost increased from 1400 to 1500".to_string(), result[0].write_text())
+    }
+
+    #[test]
+    fn steam_post_works() {
+        let html = "<html><body><div class=\"EventDetailsBody\">\
+            <div class=\"bb_h2\">General</div><ul class=\"bb_ul\"><li>Something random</li></ul>\
+            <div class=\"bb_h2\">Heroes</div><div class=\"bb_h3\">Zeus</div>\
+            <ul class=\"bb_ul\"><li><b>Arc Lightning</b><ul class=\"bb_ul\"><li>Damage increased from 85 to 100</li></ul></li></ul>\
+            </div></body></html>";
+        let result = parse_html_str(html, "7.32", &Config::default());
+
+        assert_eq!(1, result.len());
+        assert_eq!("Heroes > Zeus > Arc Lightning > Damage increased from 85 to 100".to_string(), result[0].write_text());
+        assert_eq!(Some(&BTreeSet::from(["Zeus".to_string()])), parse_sections(html).get("Heroes"))
+    }
+
+    #[test]
+    fn source_is_detected() {
+        let wiki = Html::parse_document("<div class=\"mw-parser-output\"><h2>Items</h2></div>");
+        let steam = Html::parse_document("<div class=\"EventDetailsBody\"><div class=\"bb_h2\">Items</div></div>");
+
+        assert_eq!(Source::Wiki, Source::detect(&wiki));
+        assert_eq!(Source::Steam, Source::detect(&steam));
+        assert_eq!(Source::Wiki, Source::detect(&Html::parse_document("<p>Nothing</p>")));
+        assert_eq!(None, Source::find(&Html::parse_document("<p>Nothing</p>")))
+    }
+}
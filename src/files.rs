@@ -0,0 +1,626 @@
+use log::{info, warn};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::document::{Source, parse_html_str, parse_patch_document, parse_sections};
+use scraper::Html;
+use crate::model::{DiffReport, PatchAccumulator, drop_unchanged_values, PatchChange, TextOptions};
+use crate::render::{FileFormat, render_file, render_template};
+use crate::version::{Version, VersionDates};
+
+pub const HTML_DIR: &str = "./html";
+// Read for the html dir when no dir is given on the command line
+pub const HTML_DIR_ENV: &str = "DOTA2DIFF_HTML_DIR";
+// Output files are written in the html dir under this name, with the extension of their format
+pub const OUTPUT_NAME: &str = "patch_diff";
+
+// The dirs given on the command line in order, otherwise the DOTA2DIFF_HTML_DIR env var, then HTML_DIR
+pub fn resolve_html_dirs(flags: &[String]) -> Vec<String> {
+    if !flags.is_empty() {
+        return flags.to_vec()
+    }
+    match env::var(HTML_DIR_ENV) {
+        Ok(dir) if !dir.is_empty() => vec![dir],
+        _ => vec![HTML_DIR.to_string()]
+    }
+}
+
+// The output path given on the command line, otherwise OUTPUT_NAME in the first html dir the versions are read from
+pub fn resolve_output_base(flag: Option<&str>, html_dir: &str) -> String {
+    match flag {
+        Some(base) => base.to_string(),
+        None => Path::new(html_dir).join(OUTPUT_NAME).to_string_lossy().to_string()
+    }
+}
+
+// Html files of every dir in order, keeping only the first file found for each version
+fn get_version_list(dirs: &[&str]) -> Vec<String> {
+    let mut version_file_list: Vec<String> = vec![];
+    let mut seen_labels: HashSet<String> = HashSet::new();
+
+    for dir in dirs {
+        let paths = match fs::read_dir(dir) {
+            Ok(paths) => paths,
+            Err(err) => {
+                warn!("Unable to read {}: {}", dir, err);
+                continue
+            }
+        };
+        for path in paths {
+            match path {
+                Ok(entry) => match entry.path().to_str() {
+                    Some(version) if !is_version_file(version) => info!("Skipping {}, its name is not a version", version),
+                    Some(version) => {
+                        if seen_labels.insert(version_label(version).to_string()) {
+                            version_file_list.push(version.to_string())
+                        }
+                    },
+                    None => warn!("Skipping non UTF-8 path {:?}", entry.path())
+                },
+                Err(err) => warn!("Skipping unreadable directory entry: {}", err)
+            }
+        }
+    }
+    // read_dir has no fixed order, and the range walk in get_diff_between needs oldest first
+    version_file_list.sort_by_cached_key(|version| (Version::parse(version_label(version)), version.to_string()));
+    version_file_list
+}
+
+// "./html/7.32c.html" -> "7.32c"
+fn version_label(path: &str) -> &str {
+    Path::new(path).file_stem().and_then(|stem| stem.to_str()).unwrap_or(path)
+}
+
+// Only files named like "7.32c.html" hold patch notes, leaving out the tool's own output and stray files
+fn is_version_file(path: &str) -> bool {
+    Path::new(path).extension().and_then(|extension| extension.to_str()) == Some("html")
+        && Version::parse(version_label(path)).is_some()
+}
+
+// Version labels ("7.32c") of every html file in dirs, oldest first
+pub fn list_versions(dirs: &[&str]) -> Vec<String> {
+    get_version_list(dirs).iter().map(|path| version_label(path).to_string()).collect()
+}
+
+// The two newest versions in dirs as (older, newer)
+pub fn latest_two(dirs: &[&str]) -> Option<(String, String)> {
+    let mut versions = list_versions(dirs);
+    let newest = versions.pop()?;
+    let previous = versions.pop()?;
+    Some((previous, newest))
+}
+
+fn read_html_from_file(version: &str) -> Option<String> {
+    match fs::read_to_string(version) {
+        Ok(body) => Some(body),
+        Err(err) => {
+            warn!("Skipping {}: {}", version, err);
+            None
+        }
+    }
+}
+
+pub fn get_diff_between(dirs: &[&str], a: &str, b: &str, config: &Config) -> Vec<PatchChange> {
+    if a == b {
+        warn!("Both versions are {}, there is nothing to compare", a);
+        return vec![]
+    }
+    let (old_label, new_label) = if Version::parse(a) > Version::parse(b) { (b, a) } else { (a, b) };
+    let version_list = get_version_list(dirs);
+    let mut gathering_patches = false;
+
+    let mut range_versions: Vec<String> = vec![];
+
+    for version in version_list {
+        let label = version_label(&version);
+        if !gathering_patches && label == old_label {
+            gathering_patches = true
+        }
+        if gathering_patches {
+            if label == new_label {
+                gathering_patches = false
+            }
+            range_versions.push(version);
+        }
+    }
+
+    match range_versions.last().map(|version| version_label(version)) {
+        None => warn!("No file for {}, there is nothing to compare", old_label),
+        Some(last) if last != new_label => warn!("No file for {}, the diff runs up to {} instead", new_label, last),
+        _ => ()
+    }
+
+    let missing: Vec<String> = range_versions.windows(2)
+        .filter_map(|pair| Some((Version::parse(version_label(&pair[0]))?, Version::parse(version_label(&pair[1]))?)))
+        .flat_map(|(old, new)| old.missing_before(&new))
+        .collect();
+    if !missing.is_empty() {
+        warn!("No file for {} between {} and {}, the diff may be incomplete", missing.join(", "), old_label, new_label);
+    }
+
+    let mut progress = Progress::new(range_versions.len(), config.progress, io::stderr().is_terminal());
+    let mut accumulator = PatchAccumulator::new(false).with_document_order(config.document_order);
+    for patch in parse_versions(&range_versions, config) {
+        accumulator.add_patch(patch);
+        progress.step(&mut io::stderr());
+    }
+    keep_changed(accumulator.finish(), config)
+}
+
+// Reverted absolute changes are only kept when they are going to be shown
+fn keep_changed(changes: Vec<PatchChange>, config: &Config) -> Vec<PatchChange> {
+    if config.show_reverted { changes } else { drop_unchanged_values(changes, config.unchanged_epsilon) }
+}
+
+// "Parsing version N of M" rewritten in place, left out when the output is piped
+struct Progress {
+    total: usize,
+    done: usize,
+    enabled: bool
+}
+
+impl Progress {
+    fn new(total: usize, requested: bool, is_tty: bool) -> Self {
+        Progress { total, done: 0, enabled: requested && is_tty }
+    }
+
+    fn step(&mut self, out: &mut impl Write) {
+        self.done += 1;
+        if !self.enabled {
+            return
+        }
+        let end = if self.done == self.total { "\n" } else { "" };
+        let _ = write!(out, "\rParsing version {} of {}{}", self.done, self.total, end);
+    }
+}
+
+fn parse_version_file(version: &str, config: &Config) -> Vec<PatchChange> {
+    info!("Parsing {}", version);
+    match read_html_from_file(version) {
+        Some(html) => parse_html_str(&html, version, config),
+        None => vec![]
+    }
+}
+
+// The changes of each version in order, parsed lazily one file at a time
+#[cfg(not(feature = "parallel"))]
+fn parse_versions<'a>(versions: &'a [String], config: &'a Config) -> impl Iterator<Item = Vec<PatchChange>> + 'a {
+    versions.iter().map(move |version| parse_version_file(version, config))
+}
+
+// Parses one batch of files at a time, a file per thread, and yields them in order. Only a batch of
+// parsed patches is held at once instead of the whole range, at the cost of waiting on the slowest file
+// of each batch
+#[cfg(feature = "parallel")]
+fn parse_versions<'a>(versions: &'a [String], config: &'a Config) -> impl Iterator<Item = Vec<PatchChange>> + 'a {
+    use rayon::prelude::*;
+    versions.chunks(rayon::current_num_threads().max(1))
+        .flat_map(move |batch| batch.par_iter().map(|version| parse_version_file(version, config)).collect::<Vec<_>>())
+}
+
+// Every change one version file lists, grouped by property and otherwise in document order, without diffing.
+// None when no file in dirs is for that version
+pub fn dump_version(dirs: &[&str], version: &str, config: &Config) -> Option<Vec<PatchChange>> {
+    let path = get_version_list(dirs).into_iter().find(|path| version_label(path) == version)?;
+    let mut changes = parse_version_file(&path, config);
+    changes.sort_by(|a, b| a.property().cmp(b.property()));
+    Some(changes)
+}
+
+// (version, number of changes or why it failed) for every version file in dirs, oldest first
+pub fn check_all(dirs: &[&str], config: &Config) -> Vec<(String, Result<usize, String>)> {
+    get_version_list(dirs).into_iter()
+        .map(|path| {
+            let result = fs::read_to_string(&path).map_err(|err| err.to_string()).and_then(|html| {
+                let document = Html::parse_document(&html);
+                if Source::find(&document).is_none() {
+                    return Err("no patch notes found".to_string())
+                }
+                Ok(parse_patch_document(document, &path, config).len())
+            });
+            (version_label(&path).to_string(), result)
+        })
+        .collect()
+}
+
+// The sections of every version file in dirs, merged
+pub fn list_sections(dirs: &[&str]) -> BTreeMap<String, BTreeSet<String>> {
+    let mut sections: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for version in get_version_list(dirs) {
+        let Some(html) = read_html_from_file(&version) else { continue };
+        for (h2, h3s) in parse_sections(&html) {
+            sections.entry(h2).or_default().extend(h3s);
+        }
+    }
+    sections
+}
+
+// The diff between a and b along with its range and summary, oldest version first
+pub fn get_diff_report(dirs: &[&str], a: &str, b: &str, config: &Config) -> DiffReport {
+    let (old, new) = if Version::parse(a) > Version::parse(b) { (b, a) } else { (a, b) };
+    DiffReport::new(old, new, get_diff_between(dirs, a, b, config))
+}
+
+pub fn get_diff_between_dates(dirs: &[&str], start: &str, end: &str, config: &Config) -> Result<Vec<PatchChange>, String> {
+    match VersionDates::bundled().resolve(start, end) {
+        Some((old, new)) => Ok(get_diff_between(dirs, &old, &new, config)),
+        None => Err(format!("No patch was released between {} and {}", start, end))
+    }
+}
+
+// Combines two html files given by path, whatever they are named, with their changes labelled "old" and "new"
+pub fn get_diff_of_files(old_path: &str, new_path: &str, config: &Config) -> io::Result<Vec<PatchChange>> {
+    let mut accumulator = PatchAccumulator::new(false).with_document_order(config.document_order);
+    for (path, label) in [(old_path, "old"), (new_path, "new")] {
+        info!("Parsing {}", path);
+        accumulator.add_patch(parse_html_str(&fs::read_to_string(path)?, label, config));
+    }
+    Ok(keep_changed(accumulator.finish(), config))
+}
+
+pub fn write_output(path: &str, content: &str) -> io::Result<()> {
+    fs::write(path, content)
+}
+
+// Renders the diff once per format and writes each to base.extension, returning the written paths.
+// The html output is put into template when one is given
+pub fn write_formats(report: &DiffReport, formats: &[FileFormat], base: &str, options: &TextOptions,
+                     template: Option<&str>) -> io::Result<Vec<String>> {
+    let mut written = vec![];
+    for format in formats {
+        let mut content = render_file(report, *format, options);
+        if let (FileFormat::Html, Some(template)) = (format, template) {
+            content = render_template(template, &content);
+        }
+        let path = format!("{}.{}", base, format.extension());
+        write_output(&path, &content)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::sync::Mutex;
+    use crate::{Config, DiffReport, FileFormat, PatchChange, TextOptions};
+    use crate::document::parse_html_str;
+    use crate::files::{HTML_DIR, HTML_DIR_ENV, Progress, check_all, dump_version, resolve_html_dirs, resolve_output_base, get_diff_between, get_diff_of_files, get_version_list, latest_two, list_versions, parse_versions, read_html_from_file, write_formats};
+
+    static CAPTURED_LOGS: Mutex<Vec<(log::Level, String)>> = Mutex::new(Vec::new());
+
+    struct CapturingLogger;
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED_LOGS.lock().unwrap().push((record.level(), record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger;
+
+    fn capture_logs() {
+        // Another test may have installed the logger already
+        let _ = log::set_logger(&LOGGER);
+        log::set_max_level(log::LevelFilter::Trace);
+    }
+
+    #[test]
+    fn unreadable_file_logs_warning() {
+        capture_logs();
+        let result = read_html_from_file("./html/missing_version.html");
+
+        assert!(result.is_none());
+        assert!(CAPTURED_LOGS.lock().unwrap().iter().any(|(level, message)| {
+            *level == log::Level::Warn && message.contains("./html/missing_version.html")
+        }))
+    }
+
+    #[test]
+    fn write_formats_writes_each_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("patch_diff").to_str().unwrap().to_string();
+        let changes = vec![PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32")];
+        let formats = [FileFormat::Html, FileFormat::Json, FileFormat::Markdown];
+        let result = write_formats(&DiffReport::new("7.32", "7.32a", changes), &formats, &base, &TextOptions::default(), None).unwrap();
+
+        assert_eq!(vec![format!("{}.html", base), format!("{}.json", base), format!("{}.md", base)], result);
+        for path in &result {
+            assert!(fs::read_to_string(path).unwrap().contains("Base armor"), "{}", path)
+        }
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&result[1]).unwrap()).unwrap();
+        assert_eq!(("7.32", "7.32a", 1), (json["old"].as_str().unwrap(), json["new"].as_str().unwrap(), json["summary"]["total"].as_u64().unwrap()))
+    }
+
+    #[test]
+    fn get_version_list_skips_non_version_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["7.32c.html", "patch_diff.html", "README", "7.32.md", "7.32b.json"] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        let path = dir.path().to_str().unwrap();
+
+        assert_eq!(vec![format!("{}/7.32c.html", path)], get_version_list(&[path]));
+        assert_eq!(vec!["7.32c".to_string()], list_versions(&[path]))
+    }
+
+    #[test]
+    fn progress_counts_versions() {
+        let mut out: Vec<u8> = vec![];
+        let mut progress = Progress::new(2, true, true);
+        progress.step(&mut out);
+        progress.step(&mut out);
+
+        assert_eq!("\rParsing version 1 of 2\rParsing version 2 of 2\n", String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn progress_is_suppressed_without_tty() {
+        let mut out: Vec<u8> = vec![];
+        let mut progress = Progress::new(2, true, false);
+        progress.step(&mut out);
+        progress.step(&mut out);
+
+        assert!(out.is_empty())
+    }
+
+    #[test]
+    fn html_dir_precedence_works() {
+        std::env::remove_var(HTML_DIR_ENV);
+        assert_eq!(vec![HTML_DIR], resolve_html_dirs(&[]));
+
+        std::env::set_var(HTML_DIR_ENV, "/srv/notes");
+        assert_eq!(vec!["/srv/notes"], resolve_html_dirs(&[]));
+        assert_eq!(vec!["./flag", "./other"], resolve_html_dirs(&["./flag".to_string(), "./other".to_string()]));
+        std::env::remove_var(HTML_DIR_ENV)
+    }
+
+    #[test]
+    fn output_base_follows_html_dir() {
+        assert_eq!("./html/patch_diff", resolve_output_base(None, HTML_DIR));
+        assert_eq!("/data/notes/patch_diff", resolve_output_base(None, "/data/notes"));
+        assert_eq!("out/diff", resolve_output_base(Some("out/diff"), "/data/notes"))
+    }
+
+    #[test]
+    fn check_all_reports_each_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("7.32.html"), "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 1</li><li>Something random</li></ul></div>").unwrap();
+        fs::write(dir.path().join("7.32b.html"), "<html><body><p>404 Not Found</p>").unwrap();
+        fs::write(dir.path().join("7.32c.html"), [0xff, 0xfe, 0x00]).unwrap();
+        let result = check_all(&[dir.path().to_str().unwrap()], &Config::default());
+
+        assert_eq!(3, result.len());
+        assert_eq!(("7.32".to_string(), Ok(2)), result[0]);
+        assert_eq!(("7.32b".to_string(), Err("no patch notes found".to_string())), result[1]);
+        assert!(result[2].1.is_err())
+    }
+
+    #[test]
+    fn dump_version_lists_one_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("7.32.html"), "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 1</li><li>Arc Lightning damage increased by 10</li><li>Base armor increased by 1</li></ul></div>").unwrap();
+        fs::write(dir.path().join("7.32a.html"), "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 5</li></ul></div>").unwrap();
+        let path = dir.path().to_str().unwrap();
+        let result = dump_version(&[path], "7.32", &Config::default()).unwrap();
+
+        assert_eq!(vec![
+            "Heroes > Zeus > Arc Lightning damage increased by 10".to_string(),
+            "Heroes > Zeus > Base armor increased by 1".to_string(),
+            "Heroes > Zeus > Base armor increased by 1".to_string()
+        ], result.iter().map(|change| change.write_text()).collect::<Vec<String>>());
+        assert!(dump_version(&[path], "7.33", &Config::default()).is_none())
+    }
+
+    #[test]
+    fn list_versions_works() {
+        let dir = tempfile::tempdir().unwrap();
+        for file_name in ["7.32c.html", "7.9.html", "7.32.html", "7.32b.html", "patch_diff.html", "README.md"] {
+            fs::write(dir.path().join(file_name), "").unwrap();
+        }
+        let result = list_versions(&[dir.path().to_str().unwrap()]);
+
+        assert_eq!(vec!["7.9", "7.32", "7.32b", "7.32c"], result)
+    }
+
+    #[test]
+    fn list_versions_merges_dirs() {
+        let older = tempfile::tempdir().unwrap();
+        let newer = tempfile::tempdir().unwrap();
+        fs::write(older.path().join("7.32.html"), "").unwrap();
+        fs::write(older.path().join("7.32b.html"), "").unwrap();
+        fs::write(newer.path().join("7.32b.html"), "").unwrap();
+        fs::write(newer.path().join("7.32c.html"), "").unwrap();
+        let dirs = [newer.path().to_str().unwrap(), older.path().to_str().unwrap()];
+
+        assert_eq!(vec!["7.32", "7.32b", "7.32c"], list_versions(&dirs));
+        assert_eq!(Some(("7.32b".to_string(), "7.32c".to_string())), latest_two(&dirs))
+    }
+
+    #[test]
+    fn get_version_list_prefers_first_dir() {
+        let preferred = tempfile::tempdir().unwrap();
+        let fallback = tempfile::tempdir().unwrap();
+        fs::write(preferred.path().join("7.32b.html"), "").unwrap();
+        fs::write(fallback.path().join("7.32b.html"), "").unwrap();
+        fs::write(fallback.path().join("7.32.html"), "").unwrap();
+        let preferred_path = preferred.path().to_str().unwrap();
+        let fallback_path = fallback.path().to_str().unwrap();
+
+        let mut result = get_version_list(&[preferred_path, fallback_path]);
+        result.sort();
+        let mut expected = vec![
+            format!("{}/7.32b.html", preferred_path),
+            format!("{}/7.32.html", fallback_path)
+        ];
+        expected.sort();
+
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn diff_range_follows_version_order() {
+        let dir = tempfile::tempdir().unwrap();
+        for (version, amount) in [("7.32c", 16), ("7.9", 1), ("7.32", 4), ("7.10", 2), ("7.32b", 8)] {
+            let html = format!("<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+                <ul><li>Base armor increased by {}</li></ul></div>", amount);
+            fs::write(dir.path().join(format!("{}.html", version)), html).unwrap();
+        }
+        let dirs = [dir.path().to_str().unwrap()];
+
+        for (a, b) in [("7.10", "7.32b"), ("7.32b", "7.10")] {
+            let result = get_diff_between(&dirs, a, b, &Config::default());
+            assert_eq!(vec!["Heroes > Zeus > Base armor increased by 14".to_string()],
+                result.iter().map(|change| change.write_text()).collect::<Vec<String>>())
+        }
+    }
+
+    #[test]
+    fn missing_versions_log_warning() {
+        capture_logs();
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["7.32.html", "7.32c.html"] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        get_diff_between(&[dir.path().to_str().unwrap()], "7.32", "7.32c", &Config::default());
+
+        assert!(CAPTURED_LOGS.lock().unwrap().iter().any(|(level, message)| {
+            *level == log::Level::Warn && message.contains("7.32a, 7.32b between 7.32 and 7.32c")
+        }))
+    }
+
+    #[test]
+    fn unchanged_epsilon_drops_equal_values() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("7.32.html"), "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base attack time increased from 1.7 to 1.70</li></ul></div>").unwrap();
+        fs::write(dir.path().join("7.32b.html"), "").unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let exact = get_diff_between(&[path], "7.32", "7.32b", &Config::default());
+        let tolerant = get_diff_between(&[path], "7.32", "7.32b", &Config { unchanged_epsilon: 0.001, ..Default::default() });
+
+        assert_eq!(1, exact.len());
+        assert!(tolerant.is_empty())
+    }
+
+    #[test]
+    fn reverted_absolute_change_is_kept_when_shown() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("7.32.html"), "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Cooldown decreased from 90 to 80</li></ul></div>").unwrap();
+        fs::write(dir.path().join("7.32a.html"), "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Cooldown increased from 80 to 90</li></ul></div>").unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        assert!(get_diff_between(&[path], "7.32", "7.32a", &Config::default()).is_empty());
+
+        let config = Config { show_reverted: true, ..Default::default() };
+        let result = get_diff_between(&[path], "7.32", "7.32a", &config);
+        let options = TextOptions { show_reverted: true, ..Default::default() };
+        assert_eq!(1, result.len());
+        assert!(result[0].is_unchanged());
+        assert_eq!("Heroes > Zeus > Cooldown touched (net unchanged)", result[0].write_text_with(&options));
+        assert_eq!("Heroes > Zeus > Cooldown unchanged", result[0].write_text())
+    }
+
+    #[test]
+    fn diff_of_files_works() {
+        let dir = tempfile::tempdir().unwrap();
+        let old_path = dir.path().join("before.html");
+        let new_path = dir.path().join("after.html");
+        fs::write(&old_path, "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased from 1 to 2</li><li>Arc Lightning damage increased by 10</li></ul></div>").unwrap();
+        fs::write(&new_path, "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased from 2 to 3</li></ul></div>").unwrap();
+
+        let result = get_diff_of_files(old_path.to_str().unwrap(), new_path.to_str().unwrap(), &Config::default()).unwrap();
+
+        assert_eq!(vec!["Heroes > Zeus > Arc Lightning damage increased by 10".to_string(), "Heroes > Zeus > Base armor increased from 1 to 3".to_string()],
+            result.iter().map(|change| change.write_text()).collect::<Vec<String>>());
+        assert_eq!("new", result[1].version());
+        assert!(get_diff_of_files("missing.html", new_path.to_str().unwrap(), &Config::default()).is_err())
+    }
+
+    // Compares the html written for testdata/golden byte for byte, run with UPDATE_GOLDEN=1 to rewrite expected.html
+    #[test]
+    fn html_output_matches_golden_file() {
+        let golden_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/golden");
+        let out_dir = tempfile::tempdir().unwrap();
+        let diff = get_diff_between(&[golden_dir.to_str().unwrap()], "7.32", "7.32a", &Config::default());
+        let base = out_dir.path().join("patch_diff");
+        let written = write_formats(&DiffReport::new("7.32", "7.32a", diff), &[FileFormat::Html], base.to_str().unwrap(), &TextOptions::default(), None).unwrap();
+        let rendered = fs::read_to_string(&written[0]).unwrap();
+
+        let expected_path = golden_dir.join("expected.html");
+        if std::env::var("UPDATE_GOLDEN").is_ok_and(|update| update == "1") {
+            fs::write(&expected_path, &rendered).unwrap();
+        }
+        assert_eq!(fs::read_to_string(expected_path).unwrap(), rendered)
+    }
+
+    #[test]
+    fn same_version_diff_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let html = "<div class=\"mw-parser-output\"><h2>Items</h2><h3>Blade Mail</h3>\
+            <ul><li>Duration increased from 4.5s to 5.5s</li></ul></div>";
+        fs::write(dir.path().join("7.32.html"), html).unwrap();
+        let result = get_diff_between(&[dir.path().to_str().unwrap()], "7.32", "7.32", &Config::default());
+
+        assert!(result.is_empty())
+    }
+
+    #[test]
+    fn parse_versions_keeps_version_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixtures = [
+            ("7.32.html", "<ul><li>Duration increased from 4.5s to 5.5s</li></ul>"),
+            ("7.32b.html", "<ul><li>Duration increased from 5.5s to 6.5s</li></ul>"),
+            ("7.32c.html", "<ul><li>Armor increased from 4 to 5</li></ul>")
+        ];
+        let mut versions: Vec<String> = vec![];
+        let mut expected = vec![];
+        for (file_name, items) in fixtures {
+            let html = format!("<div class=\"mw-parser-output\"><h2>Items</h2><h3>Blade Mail</h3>{}</div>", items);
+            let path = dir.path().join(file_name).to_str().unwrap().to_string();
+            fs::write(&path, &html).unwrap();
+            expected.append(&mut parse_html_str(&html, &path, &Config::default()));
+            versions.push(path);
+        }
+
+        assert_eq!(expected, parse_versions(&versions, &Config::default()).flatten().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn latest_two_works() {
+        let dir = tempfile::tempdir().unwrap();
+        for file_name in ["7.32b.html", "7.9.html", "7.32.html", "7.32c.html", "patch_diff.html"] {
+            fs::write(dir.path().join(file_name), "").unwrap();
+        }
+        let result = latest_two(&[dir.path().to_str().unwrap()]);
+
+        assert_eq!(Some(("7.32b".to_string(), "7.32c".to_string())), result)
+    }
+
+    #[test]
+    fn latest_two_needs_two_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("7.32.html"), "").unwrap();
+
+        assert_eq!(None, latest_two(&[dir.path().to_str().unwrap()]))
+    }
+}
@@ -0,0 +1,19 @@
+mod config;
+mod document;
+#[cfg(not(target_arch = "wasm32"))]
+mod files;
+mod model;
+mod render;
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+mod server;
+mod version;
+
+pub use crate::config::Config;
+pub use crate::document::{Block, DocumentParser, Source, SteamParser, WikiParser, parse_html_str, parse_patch_document, parse_sections};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::files::{HTML_DIR, HTML_DIR_ENV, OUTPUT_NAME, check_all, dump_version, get_diff_between, get_diff_between_dates, get_diff_report, get_diff_of_files, latest_two, list_sections, list_versions, resolve_html_dirs, resolve_output_base, write_formats, write_output};
+pub use crate::model::{NO_CHANGES_EXIT_CODE, SEP, UNKNOWN_VALUE, ChangeData, DiffReport, DiffSummary, PatchChange, PatchAccumulator, ParseReport, PropertyMatcher, TreePath, DiffDelta, DiffError, ExactMatch, GroupBy, OutputOrder, TextOptions, Verdict, classify_change, patch_diff, patch_diff_matching, patch_diff_with_history, diff_of_diffs, distinct_properties, find_reversions, entity_verdicts, sort_for_output, drop_unchanged_values, exclude_changes, filter_by_magnitude, no_changes_exit, only_additions, parse_numeric_value, regroup, strip_other, talent_level, unit_for_property};
+pub use crate::render::{FileFormat, render_file, render_gfm_table, render_html, render_json, render_report, render_template, render_terminal, render_verdicts};
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+pub use crate::server::{DEFAULT_BIND, Response, handle_request, serve};
+pub use crate::version::{Version, VersionDates, expand_version};
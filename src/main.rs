@@ -1,184 +1,386 @@
-use scraper::{Html, Selector, ElementRef};
+use clap::{Parser, ValueEnum};
+use log::LevelFilter;
+use std::env;
 use std::fs;
+use std::io::IsTerminal;
+use std::process;
 
-mod model;
-pub use crate::model::{PatchChange, patch_diff};
+use dota2diff::{Config, DiffReport, FileFormat, GroupBy, Source, ParseReport, TextOptions, check_all, dump_version, get_diff_between, get_diff_of_files, latest_two, list_sections, list_versions, distinct_properties, exclude_changes, expand_version, filter_by_magnitude, no_changes_exit, only_additions, render_json, render_terminal, render_verdicts, resolve_html_dirs, resolve_output_base, strip_other, write_formats};
 
-fn get_version_list() -> Vec<String> {
-    let paths = fs::read_dir("./html").unwrap();
-    let mut version_file_list: Vec<String> = vec![];
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    Html,
+    Term,
+    Json,
+    #[value(alias = "gfm")]
+    Md
+}
 
-    for path in paths {
-        let version = path.unwrap().path().to_str().unwrap().to_string();
-        version_file_list.push(version);
-    }
-    version_file_list
+#[derive(Clone, Copy, ValueEnum)]
+enum SourceFormat {
+    Wiki,
+    Steam
 }
 
-fn read_html_from_file(version: &str) -> Html {
-    let file_path = format!("{}", version);
-    let body = fs::read_to_string(file_path).unwrap();
-    Html::parse_document(&body)
+#[derive(Clone, Copy, ValueEnum)]
+enum Grouping {
+    Section,
+    Entity,
+    Verdict
 }
 
-fn parse_patch_document(document: Html, version: &str) -> Vec<PatchChange> {
-    let primary_div = Selector::parse(".mw-parser-output > *").unwrap();
+#[derive(Parser)]
+#[command(about = "Combines Dota 2 patch notes into a single net diff")]
+struct Args {
+    /// Older version of the range, e.g. 7.32 or just 32
+    #[arg(requires = "new", conflicts_with = "latest")]
+    old: Option<String>,
 
-    let mut current_h2 = "".to_string();
-    let mut current_h3 = "".to_string();
+    /// Newer version of the range, e.g. 7.32c or just 32c
+    new: Option<String>,
 
-    let mut patch_changes: Vec<PatchChange> = vec![];
+    /// Directory of the version html files, falls back to $DOTA2DIFF_HTML_DIR and then ./html.
+    /// Can be repeated, the first dir with a version's file is used
+    #[arg(long)]
+    dir: Vec<String>,
 
-    for element in document.select(&primary_div) {
-        if element.value().name() == "h2" {
-            current_h2 = element.text().next().unwrap().trim().to_string();
-        } else if element.value().name() == "h3" {
-            current_h3 = element.text().next().unwrap().trim().to_string();
-        } else if element.value().name() == "ul" {
-            let tree_loc = format!("{} > {}", current_h2, current_h3);
-            if current_h2 == "General" || current_h2 == "Additional Content" {
-                continue;
-            }
-            patch_changes.append(&mut parse_ul_element(element, tree_loc, version));
-        }
-    }
-    patch_changes
-}
+    /// Path to write the diff to without its extension, defaults to patch_diff in the first html dir
+    #[arg(long, value_name = "BASE")]
+    out: Option<String>,
 
-fn parse_ul_element(ul: ElementRef, tree_loc: String, version: &str) -> Vec<PatchChange> {
-    let mut ul_changes: Vec<PatchChange> = vec![];
-    let b_selector = Selector::parse("b").unwrap();
-    let mut b_values = ul.select(&b_selector);
-    let mut next_b = b_values.next();
-    let mut current_b = "".to_string();
-
-    let change_lines = ul.text();
-    for mut change_line in change_lines {
-        change_line = change_line.trim();
-        if change_line == "" {
-            continue
-        }
+    /// Diff two html files by path instead of versions from the html dir
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with_all = ["old", "latest"])]
+    files: Option<Vec<String>>,
 
-        change_line = change_line.split(" (").next().unwrap();
+    /// Also log progress and lines that could not be parsed
+    #[arg(long, conflicts_with = "quiet")]
+    verbose: bool,
 
-        if next_b != None {
-            if change_line == next_b.unwrap().text().next().unwrap() {
-                current_b = change_line.to_string();
-                next_b = b_values.next();
-                continue;
-            }
-        }
+    /// Only log errors
+    #[arg(long)]
+    quiet: bool,
 
-        let mut tree_location = tree_loc.to_string();
-        if current_b != "".to_string() {
-            tree_location.push_str(&format!(" > {}", current_b));
-        }
+    /// Print the available versions instead of diffing
+    #[arg(long)]
+    list: bool,
 
-        let parsed_text = PatchChange::parse_text(change_line, tree_location, &version);
-        ul_changes.push(parsed_text);
-    }
+    /// Print the sections and entities found in the version files instead of diffing
+    #[arg(long)]
+    list_sections: bool,
 
-    ul_changes
-}
+    /// Print every property changed in the range instead of writing the diff
+    #[arg(long)]
+    list_properties: bool,
+
+    /// Print one buff and nerf count per hero or item instead of writing the diff
+    #[arg(long)]
+    verdicts: bool,
+
+    /// Parse every version file and report the ones that failed instead of diffing
+    #[arg(long)]
+    check_all: bool,
+
+    /// Print every change parsed from one version, e.g. 7.32c or just 32c, instead of diffing. JSON with --format json
+    #[arg(long, value_name = "VERSION", conflicts_with_all = ["old", "latest", "files"])]
+    dump: Option<String>,
+
+    /// Diff the two newest versions available
+    #[arg(long)]
+    latest: bool,
+
+    /// Keep changes that were reverted by a later patch instead of dropping them
+    #[arg(long)]
+    show_reverted: bool,
+
+    /// Note the patch each change got its latest value in after the html changes
+    #[arg(long)]
+    show_version: bool,
 
-fn get_diff_between(a: &str, b: &str) -> Vec<PatchChange> {
-    let mut old_path = format!("./html/{}.html", a);
-    let mut new_path = format!("./html/{}.html", b);
+    /// Keep notes that could not be parsed in the order the patch lists them instead of sorting them
+    #[arg(long)]
+    document_order: bool,
+
+    /// Group the html output by section, entity or buff/nerf verdict instead of by its headers
+    #[arg(long, value_enum)]
+    group_by: Option<Grouping>,
+
+    /// Write grouped changes inline in the html output instead of nesting them
+    #[arg(long)]
+    compact: bool,
+
+    /// Leave out changes whose property matches this regex or text, can be repeated
+    #[arg(long, value_name = "PATTERN")]
+    exclude: Vec<String>,
+
+    /// Cut notes that could not be parsed down to this many characters
+    #[arg(long, value_name = "CHARS")]
+    max_note_length: Option<usize>,
+
+    /// Leave out changes that could not be parsed into values
+    #[arg(long)]
+    structured_only: bool,
+
+    /// Only keep properties that had no value before the range, like "Now has a 50 Mana Cost"
+    #[arg(long)]
+    new_only: bool,
+
+    /// Leave out numeric changes moving less than this, by difference and by percent
+    #[arg(long, value_name = "MIN")]
+    min_magnitude: Option<f32>,
+
+    /// JSON file mapping old hero, item or ability names to new ones
+    #[arg(long, value_name = "FILE")]
+    renames: Option<String>,
+
+    /// Show how many versions have been parsed when stderr is a terminal
+    #[arg(long)]
+    progress: bool,
+
+    /// Leave out absolute changes whose old and new numbers differ by at most this much
+    #[arg(long, value_name = "EPSILON", default_value_t = 0.0)]
+    unchanged_epsilon: f32,
+
+    /// Write heroes and items under their canonical name when the notes use a known alias
+    #[arg(long)]
+    roster: bool,
+
+    /// Markup of the version files, detected from each file when not given
+    #[arg(long, value_enum)]
+    source: Option<SourceFormat>,
+
+    /// HTML file to write the diff into, at {{content}}
+    #[arg(long, value_name = "FILE")]
+    template: Option<String>,
+
+    /// Exit with an error when more than this percent of the changes could not be parsed
+    #[arg(long, value_name = "THRESHOLD")]
+    fail_on_unparsed: Option<f32>,
+
+    /// Serve diffs at GET /diff?old=7.32&new=7.32c&format=json on this port instead of writing them
+    #[cfg(feature = "server")]
+    #[arg(long, value_name = "PORT")]
+    serve: Option<u16>,
+
+    /// Address to serve on, only this machine by default
+    #[cfg(feature = "server")]
+    #[arg(long, value_name = "ADDR", default_value = dota2diff::DEFAULT_BIND, requires = "serve")]
+    bind: String,
+
+    /// Comma-separated formats to write to patch_diff.html, .json and .md in the first html dir or at --out, or term to print the diff
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "html")]
+    format: Vec<OutputFormat>
+}
 
-    if a > b {
-        old_path = format!("./html/{}.html", b);
-        new_path = format!("./html/{}.html", a);
+impl Args {
+    fn log_level(&self) -> LevelFilter {
+        if self.quiet {
+            LevelFilter::Error
+        } else if self.verbose {
+            LevelFilter::Info
+        } else {
+            LevelFilter::Warn
+        }
     }
-    let version_list = get_version_list();
-    let mut gathering_patches = false;
+}
 
-    let mut combined_patches: Vec<PatchChange> = vec![];
+// Colors are only used on an interactive terminal, and never when NO_COLOR is set
+fn use_color() -> bool {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    !no_color && std::io::stdout().is_terminal()
+}
 
-    for version in version_list {
-        if !gathering_patches && version == old_path {
-            gathering_patches = true
+// The full version for input, which has to have a file in one of html_dirs
+fn find_version(input: &str, available: &[String], html_dirs: &[&str]) -> Result<String, String> {
+    let version = expand_version(input, available)?;
+    if !available.contains(&version) {
+        return Err(format!("No patch notes for {} in {}", version, html_dirs.join(", ")))
+    }
+    Ok(version)
+}
+
+// The (old, new) versions to diff, from --latest, the positional versions or the default range
+fn resolve_range(args: &Args, html_dirs: &[&str]) -> (String, String) {
+    if args.latest {
+        match latest_two(html_dirs) {
+            Some(versions) => versions,
+            None => {
+                eprintln!("--latest needs at least two versions in {}", html_dirs.join(", "));
+                process::exit(1)
+            }
         }
-        if gathering_patches {
-            if version == new_path {
-                gathering_patches = false
+    } else if let (Some(old), Some(new)) = (&args.old, &args.new) {
+        let available = list_versions(html_dirs);
+        match (find_version(old, &available, html_dirs), find_version(new, &available, html_dirs)) {
+            (Ok(old), Ok(new)) => (old, new),
+            (Err(err), _) | (_, Err(err)) => {
+                eprintln!("{}", err);
+                process::exit(1)
             }
-            let document = read_html_from_file(&version);
-            combined_patches.append(&mut parse_patch_document(document, &version))
         }
+    } else {
+        ("7.32".to_string(), "7.32c".to_string())
     }
-    patch_diff(combined_patches)
 }
 
-fn save_diff_as_html(diff_result: Vec<PatchChange>) {
-    let mut result = "<div>".to_string();
+fn main() {
+    let args = Args::parse();
+    env_logger::Builder::new().filter_level(args.log_level()).init();
 
-    let mut current_h2 = "".to_string();
-    let mut current_h3 = "".to_string();
-    let mut current_b = "".to_string();
+    let html_dirs = resolve_html_dirs(&args.dir);
+    let html_dirs: Vec<&str> = html_dirs.iter().map(String::as_str).collect();
 
-    for change in diff_result {
-        let change_text = change.write_text();
+    if args.list {
+        for version in list_versions(&html_dirs) {
+            println!("{}", version);
+        }
+        return
+    }
 
-        // Handling RelativeChangee values of 0 (no net change between patches)
-        if change_text.contains("unchanged") {
-            continue
+    if args.list_sections {
+        for (section, entities) in list_sections(&html_dirs) {
+            println!("{}", section);
+            for entity in entities {
+                println!("  {}", entity);
+            }
         }
+        return
+    }
 
-        let headers: Vec<&str> = change_text.split(" > ").collect();
+    let mut config = Config {
+        progress: args.progress,
+        unchanged_epsilon: args.unchanged_epsilon,
+        show_reverted: args.show_reverted,
+        document_order: args.document_order,
+        ..Default::default()
+    };
+    config.source = args.source.map(|source| match source {
+        SourceFormat::Wiki => Source::Wiki,
+        SourceFormat::Steam => Source::Steam
+    });
+    if args.roster {
+        config.load_bundled_roster();
+    }
+    if let Some(path) = &args.renames {
+        let loaded = fs::read_to_string(path).map_err(|err| err.to_string()).and_then(|json| config.load_renames(&json));
+        if let Err(err) = loaded {
+            eprintln!("Unable to load renames from {}: {}", path, err);
+            process::exit(1)
+        }
+    }
 
-        if headers[0] != current_h2 {
-            if current_h2 == "".to_string() {
-                result.push_str("<h2>");
-            } else {
-                if current_b != "".to_string() {
-                    result.push_str("</ul></li>")
-                }
-                result.push_str("</ul><h2>")
+    if args.check_all {
+        let results = check_all(&html_dirs, &config);
+        let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+        for (version, result) in &results {
+            match result {
+                Ok(count) => println!("OK    {} ({} changes)", version, count),
+                Err(err) => println!("ERROR {}: {}", version, err)
             }
-            result.push_str(&headers[0]);
-            result.push_str("</h2>");
-            current_h2 = headers[0].to_string();
-            current_h3 = "".to_string();
-            current_b = "".to_string();
         }
-        if headers[1] != current_h3 {
-            if current_h3 == "".to_string() {
-                result.push_str("<h3>");
-            } else {
-                if current_b != "".to_string() {
-                    result.push_str("</ul></li>")
-                }
-                result.push_str("</ul><h3>");
-            }
-            result.push_str(&headers[1]);
-            result.push_str("</h3><ul>");
-            current_h3 = headers[1].to_string();
-            current_b = "".to_string();
+        println!("{} ok, {} failed", results.len() - failed, failed);
+        if failed > 0 {
+            process::exit(1)
         }
-        if headers.len() == 4 {
-            if headers[2] != current_b {
-                if current_b == "".to_string() {
-                    result.push_str("<li>");
-                } else {
-                    result.push_str("</ul></li><li>");
+        return
+    }
+
+    if let Some(version) = &args.dump {
+        let dumped = find_version(version, &list_versions(&html_dirs), &html_dirs)
+            .and_then(|version| dump_version(&html_dirs, &version, &config).ok_or(format!("No patch notes for {} in {}", version, html_dirs.join(", "))));
+        match dumped {
+            Ok(changes) if args.format.contains(&OutputFormat::Json) => println!("{}", render_json(&changes, &TextOptions { show_reverted: true, ..Default::default() })),
+            Ok(changes) => {
+                for change in changes {
+                    println!("{}", change.write_text());
                 }
-                result.push_str(&headers[2]);
-                result.push_str("<ul>");
-                current_b = headers[2].to_string();
+            },
+            Err(err) => {
+                eprintln!("{}", err);
+                process::exit(1)
             }
         }
+        return
+    }
 
-        result.push_str(&format!("<li>{}</li>", headers[headers.len() - 1]));
+    #[cfg(feature = "server")]
+    if let Some(port) = args.serve {
+        if let Err(err) = dota2diff::serve(&args.bind, port, &html_dirs, &config) {
+            eprintln!("Unable to serve on {}:{}: {}", args.bind, port, err);
+            process::exit(1)
+        }
+        return
     }
 
-    if current_b == "".to_string() {
-        result.push_str("</ul></div>");
+    let (old, new) = match &args.files {
+        Some(files) => (files[0].to_string(), files[1].to_string()),
+        None => resolve_range(&args, &html_dirs)
+    };
+    let diff = if args.files.is_some() {
+        get_diff_of_files(&old, &new, &config).unwrap_or_else(|err| {
+            eprintln!("Unable to diff {} and {}: {}", old, new, err);
+            process::exit(1)
+        })
     } else {
-        result.push_str("</ul></li></ul></div>");
+        get_diff_between(&html_dirs, &old, &new, &config)
+    };
+    let report = ParseReport::from_changes(&diff);
+    let diff = if args.structured_only { strip_other(diff) } else { diff };
+    let diff = if args.new_only { only_additions(diff) } else { diff };
+    let diff = exclude_changes(diff, &args.exclude);
+    let diff = match args.min_magnitude {
+        Some(min) => filter_by_magnitude(diff, min),
+        None => diff
+    };
+    if args.list_properties {
+        for property in distinct_properties(&diff) {
+            println!("{}", property);
+        }
+        return
+    }
+    if args.verdicts {
+        print!("{}", render_verdicts(&diff));
+        return
     }
-    fs::write("./html/patch_diff.html", result).expect("Unable to write file");
-}
 
-fn main() {
-    save_diff_as_html(get_diff_between("7.32", "7.32c"))
+    let group_by = args.group_by.map(|grouping| match grouping {
+        Grouping::Section => GroupBy::Section,
+        Grouping::Entity => GroupBy::Entity,
+        Grouping::Verdict => GroupBy::Verdict
+    });
+    let options = TextOptions { show_reverted: args.show_reverted, show_version: args.show_version, compact: args.compact, max_other_len: args.max_note_length, group_by, ..Default::default() };
+    let diff_report = DiffReport::new(&old, &new, diff);
+    if args.format.contains(&OutputFormat::Term) {
+        print!("{}", render_terminal(&diff_report.changes, use_color(), &options));
+    }
+
+    let template = args.template.as_ref().map(|path| fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Unable to read template {}: {}", path, err);
+        process::exit(1)
+    }));
+    let formats: Vec<FileFormat> = args.format.iter()
+        .filter_map(|format| match format {
+            OutputFormat::Html => Some(FileFormat::Html),
+            OutputFormat::Json => Some(FileFormat::Json),
+            OutputFormat::Md => Some(FileFormat::Markdown),
+            OutputFormat::Term => None
+        })
+        .collect();
+    let output_base = resolve_output_base(args.out.as_deref(), html_dirs[0]);
+    let written = write_formats(&diff_report, &formats, &output_base, &options, template.as_deref());
+    if let Err(err) = written {
+        eprintln!("Unable to write the diff: {}", err);
+        process::exit(1)
+    }
+
+    if let Some(threshold) = args.fail_on_unparsed {
+        if !report.passes(threshold) {
+            eprintln!("{:.1}% of changes could not be parsed, more than the allowed {}%", report.unparsed_percent(), threshold);
+            process::exit(1)
+        }
+    }
+
+    if let Some((code, message)) = no_changes_exit(&diff_report.changes, &options, &old, &new) {
+        eprintln!("{}", message);
+        process::exit(code)
+    }
 }
@@ -0,0 +1,464 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::{ALL_LEVELS, SEP, UNKNOWN_VALUE, GroupBy, DECIMAL_SCALE, absolute_change_direction, infer_unit, is_talent_swap, parse_numeric_value, unit_for_property, uniform_value, with_unit, write_signed_thousandths, write_thousandths};
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[allow(clippy::enum_variant_names)]
+pub enum ChangeData {
+    AbsoluteChange(String, String),
+    RelativeChange(i32),
+    // A relative change with decimals, in thousandths so that "by 0.1" is 100
+    RelativeDecimalChange(i64),
+    // Whether a constraint like "Can be cast while rooted" held before and after
+    ToggleChange(bool, bool),
+    // What was taken out, e.g. the "+300 Health" of a removed talent
+    RemovedChange(String),
+    OtherChange(String)
+}
+
+impl ChangeData {
+    // Orders the variants the same way the derived Ord does, with both relative variants sharing a rank
+    // so whole and decimal amounts of one property are combined
+    pub(super) fn variant_rank(&self) -> u8 {
+        match self {
+            ChangeData::AbsoluteChange(_, _) => 0,
+            ChangeData::RelativeChange(_) | ChangeData::RelativeDecimalChange(_) => 1,
+            ChangeData::ToggleChange(_, _) => 2,
+            ChangeData::RemovedChange(_) => 3,
+            ChangeData::OtherChange(_) => 4
+        }
+    }
+
+    pub(super) fn variant_eq(a: &ChangeData, b: &ChangeData) -> bool {
+        a.variant_rank() == b.variant_rank()
+    }
+
+    pub(super) fn thousandths(&self) -> Option<i64> {
+        match self {
+            ChangeData::RelativeChange(value) => Some(*value as i64 * DECIMAL_SCALE),
+            ChangeData::RelativeDecimalChange(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    // (new - old) / |old| * 100 for an AbsoluteChange between two single numbers. A change from 0,
+    // like "Now has a 50 Mana Cost", is an infinite increase (or decrease), while text values give None
+    pub fn percent_delta(&self) -> Option<f32> {
+        let ChangeData::AbsoluteChange(old, new) = self else { return None };
+        let (old, new) = (parse_numeric_value(old)?, parse_numeric_value(new)?);
+        if old == 0.0 {
+            return Some(if new > 0.0 { f32::INFINITY } else if new < 0.0 { f32::NEG_INFINITY } else { 0.0 })
+        }
+        Some((new - old) / old.abs() * 100.0)
+    }
+
+    fn write_step(&self) -> String {
+        match self {
+            ChangeData::AbsoluteChange(old, new) => format!("{} → {}", old, new),
+            ChangeData::RelativeChange(value) => format!("{:+}", value),
+            ChangeData::RelativeDecimalChange(value) => write_signed_thousandths(*value),
+            ChangeData::ToggleChange(_, true) => "can now".to_string(),
+            ChangeData::ToggleChange(_, false) => "can no longer".to_string(),
+            ChangeData::RemovedChange(value) => format!("{} removed", value),
+            ChangeData::OtherChange(value) => value.to_string()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TextOptions {
+    // Append the per-patch steps recorded by patch_diff_with_history
+    pub history: bool,
+    // Write "to 100 (all levels)" instead of "to 100/100/100/100"
+    pub collapse_uniform: bool,
+    // Keep changes that cancelled out across patches, written as "touched (net unchanged)"
+    pub show_reverted: bool,
+    // Write relative changes with a recorded history as "net +1 (+1, +1, -1)"
+    pub net_delta: bool,
+    // Write "(since 7.32b)" after each html change, the version of its latest value
+    pub show_version: bool,
+    // Write "by 2s" for relative changes whose property implies a unit
+    pub relative_units: bool,
+    // Write group changes inline as "Scepter: ..." instead of nesting a list for each group
+    pub compact: bool,
+    // Written between the levels of the property path instead of SEP
+    pub separator: Option<String>,
+    // OtherChange text longer than this many characters is cut short with "…", for runs of text from a bad parse
+    pub max_other_len: Option<usize>,
+    // Group the html output this way instead of by its headers
+    pub group_by: Option<GroupBy>
+}
+
+// Where a change sits in the notes, e.g. ["Heroes", "Zeus", "Arc Lightning", "Cooldown"]
+#[derive(Debug, Clone, Default, PartialEq, PartialOrd, Eq, Ord)]
+pub struct TreePath(Vec<String>);
+
+impl TreePath {
+    pub fn push(&mut self, segment: &str) {
+        self.0.push(segment.to_string())
+    }
+
+    pub fn join(&self) -> String {
+        self.join_with(SEP)
+    }
+
+    pub fn join_with(&self, separator: &str) -> String {
+        self.0.join(separator)
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+
+    pub fn rename(&mut self, renames: &BTreeMap<String, String>) {
+        for segment in self.0.iter_mut() {
+            if let Some(new_name) = renames.get(segment) {
+                *segment = new_name.to_string();
+            }
+        }
+    }
+
+    pub(super) fn last(&self) -> &str {
+        self.0.last().map(|segment| segment.as_str()).unwrap_or("")
+    }
+}
+
+impl From<&str> for TreePath {
+    fn from(path: &str) -> Self {
+        TreePath(path.split(SEP).map(|segment| segment.to_string()).collect())
+    }
+}
+
+impl From<String> for TreePath {
+    fn from(path: String) -> Self {
+        TreePath::from(path.as_str())
+    }
+}
+
+impl fmt::Display for TreePath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.join())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
+pub struct PatchChange {
+    pub(super) property: TreePath,
+    pub(super) version: String,
+    pub(super) data: ChangeData,
+    // Inferred from the values or the property name, only for numeric AbsoluteChanges
+    pub(super) unit: Option<String>,
+    // e.g. "per stack", written after the property name
+    pub(super) qualifier: Option<String>,
+    // (version, original change) for every patch folded into this change, only kept when requested
+    pub(super) history: Vec<(String, ChangeData)>,
+    // Set by PatchAccumulator when no later patch of the range has the section this change is in
+    pub(super) old_only_section: bool,
+    // The notes said the value is the same "at all levels" of a leveled ability
+    pub(super) all_levels: bool
+}
+
+impl PatchChange {
+    /// Builds a change at a " > " separated property path, e.g. for fixtures or changes from another source.
+    ///
+    /// ```
+    /// use dota2diff::{ChangeData, PatchChange};
+    ///
+    /// let change = PatchChange::new("Heroes > Zeus > Base armor", "7.32", ChangeData::RelativeChange(1));
+    ///
+    /// assert_eq!("Base armor", change.property().segments()[2]);
+    /// assert_eq!("7.32", change.version());
+    /// assert_eq!(&ChangeData::RelativeChange(1), change.data());
+    /// assert_eq!("Heroes > Zeus > Base armor increased by 1", change.write_text());
+    /// ```
+    pub fn new(property: &str, version: &str, data: ChangeData) -> Self {
+        PatchChange::at(TreePath::from(property), version, data)
+    }
+
+    pub(super) fn at(property: TreePath, version: &str, data: ChangeData) -> Self {
+        let unit = match &data {
+            ChangeData::AbsoluteChange(old, new) => {
+                infer_unit(property.last(), new).or_else(|| infer_unit(property.last(), old))
+            },
+            _ => None
+        };
+        PatchChange {
+            property,
+            version: version.to_string(),
+            data,
+            unit,
+            qualifier: None,
+            history: vec![],
+            old_only_section: false,
+            all_levels: false
+        }
+    }
+
+    pub(super) fn with_qualifier(mut self, qualifier: Option<String>) -> Self {
+        self.qualifier = qualifier;
+        self
+    }
+
+    pub fn rename(&mut self, renames: &BTreeMap<String, String>) {
+        self.property.rename(renames)
+    }
+
+    pub fn property(&self) -> &TreePath {
+        &self.property
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn data(&self) -> &ChangeData {
+        &self.data
+    }
+
+    pub fn is_old_only_section(&self) -> bool {
+        self.old_only_section
+    }
+
+    pub fn applies_to_all_levels(&self) -> bool {
+        self.all_levels
+    }
+
+    // A net change of 0 can only come out of patch_diff by folding opposing changes together,
+    // so these were always modified by some patch along the way
+    pub fn is_unchanged(&self) -> bool {
+        match self.data {
+            ChangeData::RelativeChange(_) | ChangeData::RelativeDecimalChange(_) => self.data.thousandths() == Some(0),
+            ChangeData::ToggleChange(old, new) => old == new,
+            ChangeData::AbsoluteChange(ref old, ref new) => old == new,
+            _ => false
+        }
+    }
+
+    pub fn write_text(&self) -> String {
+        self.write_text_with(&TextOptions::default())
+    }
+
+    pub fn write_text_with(&self, options: &TextOptions) -> String {
+        let mut segments = self.headers().to_vec();
+        segments.push(self.write_line_with(options));
+        segments.join(options.separator.as_deref().unwrap_or(SEP))
+    }
+
+    // The sections and groups the change is listed under, without the change itself
+    pub fn headers(&self) -> &[String] {
+        let segments = self.property.segments();
+        match self.data {
+            ChangeData::OtherChange(_) => segments,
+            _ => &segments[..segments.len().saturating_sub(1)]
+        }
+    }
+
+    // The change without its path, which parse_text turns back into the same ChangeData for numeric changes.
+    // That does not hold for OtherChange, an unknown old value ("changed to 5") or an inferred unit ("1.7" -> "1.7s")
+    pub fn write_text_line(&self) -> String {
+        self.write_line_with(&TextOptions::default())
+    }
+
+    // The change itself, e.g. "Cooldown decreased from 90 to 80"
+    pub fn write_line_with(&self, options: &TextOptions) -> String {
+        let property = match &self.qualifier {
+            Some(qualifier) => format!("{} {}", self.property.last(), qualifier),
+            None => self.property.last().to_string()
+        };
+        let data = &self.data;
+
+        let text = match data {
+            ChangeData::AbsoluteChange(old, new) if old == new && options.show_reverted => {
+                return format!("{} touched (net unchanged)", property)
+            },
+            ChangeData::AbsoluteChange(old, new) if old == new => return format!("{} unchanged", property),
+            ChangeData::AbsoluteChange(old, new) => {
+                if is_talent_swap(&self.property) {
+                    format!("{} {} replaced with {}", property, old, new)
+                } else if old == UNKNOWN_VALUE {
+                    format!("{} changed to {}", property, with_unit(new, &self.unit))
+                } else {
+                    let direction = absolute_change_direction(old, new);
+                    let old = with_unit(old, &self.unit);
+                    match uniform_value(new) {
+                        Some(value) if options.collapse_uniform => {
+                            format!("{} {} from {} to {} (all levels)", property, direction, old, with_unit(value, &self.unit))
+                        },
+                        _ => format!("{} {} from {} to {}", property, direction, old, with_unit(new, &self.unit))
+                    }
+                }
+            },
+            ChangeData::RelativeChange(_) | ChangeData::RelativeDecimalChange(_) if options.net_delta && !self.history.is_empty() => {
+                let steps: Vec<String> = self.history.iter().map(|(_, data)| data.write_step()).collect();
+                return format!("{} net {} ({})", property, write_signed_thousandths(data.thousandths().unwrap()), steps.join(", "))
+            },
+            ChangeData::RelativeChange(_) | ChangeData::RelativeDecimalChange(_) => {
+                let value = data.thousandths().unwrap();
+                let mut direction = "increased".to_string();
+                if value < 0 {
+                    direction = "decreased".to_string()
+                } else if value == 0 && options.show_reverted {
+                    return format!("{} touched (net unchanged)", property)
+                } else if value == 0 {
+                    return format!("{} unchanged", property)
+                }
+                let unit = unit_for_property(self.property.last()).filter(|_| options.relative_units).unwrap_or("");
+                format!("{} {} by {}{}", property, direction, write_thousandths(value.abs()), unit)
+            },
+            ChangeData::ToggleChange(old, new) if old == new && options.show_reverted => {
+                return format!("{} touched (net unchanged)", property)
+            },
+            ChangeData::ToggleChange(old, new) if old == new => return format!("{} unchanged", property),
+            ChangeData::ToggleChange(_, new) => {
+                let constraint = property.strip_prefix("Can ").unwrap_or(&property);
+                format!("Can {} {}", if *new { "now" } else { "no longer" }, constraint)
+            },
+            ChangeData::RemovedChange(value) => format!("{} {} removed", property, value),
+            ChangeData::OtherChange(value) => match options.max_other_len {
+                Some(max_len) if value.chars().count() > max_len => format!("{}…", value.chars().take(max_len).collect::<String>()),
+                _ => value.to_string()
+            }
+        };
+        let text = if self.all_levels { format!("{} {}", text, ALL_LEVELS) } else { text };
+
+        if !options.history || self.history.is_empty() {
+            return text
+        }
+        let steps: Vec<String> = self.history.iter()
+            .map(|(version, data)| format!("{}: {}", version, data.write_step()))
+            .collect();
+        format!("{} ({})", text, steps.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{SEP, ChangeData, PatchChange, TextOptions, patch_diff_with_history, unit_for_property};
+
+    #[test]
+    fn long_other_change_is_truncated() {
+        let text = "a".repeat(1000);
+        let change = PatchChange::new("Heroes > Zeus", "7.32", ChangeData::OtherChange(text.to_string()));
+        let options = TextOptions { max_other_len: Some(200), ..Default::default() };
+
+        assert_eq!(format!("{}…", "a".repeat(200)), change.write_line_with(&options));
+        assert_eq!(text, change.write_line_with(&TextOptions::default()));
+        assert_eq!(&ChangeData::OtherChange(text), change.data())
+    }
+
+    #[test]
+    fn percent_delta_works() {
+        let absolute = |old: &str, new: &str| ChangeData::AbsoluteChange(old.to_string(), new.to_string());
+
+        assert_eq!(Some(25.0), absolute("80", "100").percent_delta());
+        assert_eq!(Some(-20.0), absolute("1,000 gold", "800 gold").percent_delta());
+        assert_eq!(Some(40.0), absolute("-5", "-3").percent_delta());
+        assert_eq!(Some(f32::INFINITY), absolute("0", "50").percent_delta());
+        assert_eq!(Some(f32::NEG_INFINITY), absolute("0", "-1").percent_delta());
+        assert_eq!(Some(0.0), absolute("0", "0").percent_delta());
+        assert_eq!(None, absolute("+15 Damage", "+20 Damage").percent_delta());
+        assert_eq!(None, absolute("1/2/3", "2/3/4").percent_delta());
+        assert_eq!(None, ChangeData::RelativeChange(10).percent_delta());
+        assert_eq!(None, ChangeData::OtherChange("Something random".to_string()).percent_delta())
+    }
+
+    #[test]
+    fn abs_change_write_works() {
+        let change = PatchChange::new(
+            "Items > Blade Mail > Duration",
+            "7.32",
+            ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string())
+        );
+        let result = change.write_text();
+        assert_eq!("Items > Blade Mail > Duration increased from 4.5s to 5.5s".to_string(), result)
+    }
+
+    #[test]
+    fn rel_change_write_works() {
+        let change = PatchChange::new(
+            "Heroes > Zeus > Base armor",
+            "7.32",
+            ChangeData::RelativeChange(1)
+        );
+        let result = change.write_text();
+        assert_eq!("Heroes > Zeus > Base armor increased by 1".to_string(), result)
+    }
+
+    #[test]
+    fn other_change_write_works() {
+        let change = PatchChange::new(
+            "Heroes > Zeus",
+            "7.32",
+            ChangeData::OtherChange("Random Change".to_string())
+        );
+        let result = change.write_text();
+        assert_eq!("Heroes > Zeus > Random Change".to_string(), result)
+    }
+
+    #[test]
+    fn net_delta_writes_each_patch() {
+        let property = "Heroes > Zeus > Base armor".to_string();
+        let changes = vec![
+            PatchChange::new(&property, "7.32", ChangeData::RelativeChange(1)),
+            PatchChange::new(&property, "7.32a", ChangeData::RelativeChange(1)),
+            PatchChange::new(&property, "7.32b", ChangeData::RelativeChange(-1))
+        ];
+        let result = patch_diff_with_history(changes);
+
+        assert_eq!(
+            "Heroes > Zeus > Base armor net +1 (+1, +1, -1)".to_string(),
+            result[0].write_text_with(&TextOptions { net_delta: true, ..Default::default() })
+        );
+        assert_eq!("Heroes > Zeus > Base armor increased by 1".to_string(), result[0].write_text())
+    }
+
+    #[test]
+    fn collapsed_uniform_write_works() {
+        let change = PatchChange::parse_text(
+            "Mana cost reduced from 100/110/120/130 to 100/100/100/100",
+            "Heroes > Zeus > Arc Lightning".to_string(),
+            "7.32"
+        );
+        let options = TextOptions { collapse_uniform: true, ..Default::default() };
+
+        assert_eq!(
+            "Heroes > Zeus > Arc Lightning > Mana cost decreased from 100/110/120/130 to 100 (all levels)".to_string(),
+            change.write_text_with(&options)
+        );
+        assert_eq!(
+            "Heroes > Zeus > Arc Lightning > Mana cost decreased from 100/110/120/130 to 100/100/100/100".to_string(),
+            change.write_text()
+        )
+    }
+
+    #[test]
+    fn inferred_unit_is_written() {
+        let change_line = "Base attack time reduced from 1.7 to 1.6s";
+        let result = PatchChange::parse_text(change_line, "Heroes > Zeus".to_string(), "7.32");
+        assert_eq!("Heroes > Zeus > Base attack time decreased from 1.7s to 1.6s".to_string(), result.write_text())
+    }
+
+    #[test]
+    fn relative_unit_is_written_when_requested() {
+        let options = TextOptions { relative_units: true, ..Default::default() };
+        let duration = PatchChange::parse_text("Duration increased by 2", "Items > Blade Mail".to_string(), "7.32");
+        let armor = PatchChange::parse_text("Armor increased by 2", "Items > Blade Mail".to_string(), "7.32");
+
+        assert_eq!(Some("s"), unit_for_property("Items > Blade Mail > Duration"));
+        assert_eq!(None, unit_for_property("Items > Blade Mail > Armor"));
+        assert_eq!("Items > Blade Mail > Duration increased by 2s".to_string(), duration.write_text_with(&options));
+        assert_eq!("Items > Blade Mail > Duration increased by 2".to_string(), duration.write_text());
+        assert_eq!("Items > Blade Mail > Armor increased by 2".to_string(), armor.write_text_with(&options))
+    }
+
+    #[test]
+    fn custom_separator_works() {
+        let change = PatchChange::parse_text("Cooldown > 5s now", format!("Heroes{}Zeus", SEP), "7.32");
+        let options = TextOptions { separator: Some(" / ".to_string()), ..Default::default() };
+
+        assert_eq!(vec!["Heroes".to_string(), "Zeus".to_string()], change.headers());
+        assert_eq!("Heroes / Zeus / Cooldown > 5s now".to_string(), change.write_text_with(&options));
+        assert_eq!("Heroes > Zeus > Cooldown > 5s now".to_string(), change.write_text())
+    }
+}
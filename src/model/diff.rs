@@ -0,0 +1,669 @@
+use log::warn;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::{ChangeData, PatchChange, TreePath};
+
+// Why two ChangeData could not be combined into one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffError {
+    VariantMismatch,
+    // The sum of two relative amounts does not fit, named by the variant
+    Overflow(&'static str),
+    // Removals and unparsed text have no value to carry over, named by the variant
+    Untracked(&'static str)
+}
+
+impl fmt::Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DiffError::VariantMismatch => write!(f, "ChangeData variants are not equal"),
+            DiffError::Overflow(variant) => write!(f, "{} sum overflows", variant),
+            DiffError::Untracked(variant) => write!(f, "ChangeData::{} does not track diff", variant)
+        }
+    }
+}
+
+impl std::error::Error for DiffError {}
+
+impl ChangeData {
+    /// Folds a later change of a property into an earlier one: the old value of the first and the new
+    /// value of the second for absolute changes and toggles, the sum of both for relative changes.
+    ///
+    /// ```
+    /// use dota2diff::{ChangeData, DiffError};
+    ///
+    /// let first = ChangeData::RelativeChange(2);
+    /// let second = ChangeData::RelativeChange(-3);
+    ///
+    /// assert_eq!(Ok(ChangeData::RelativeChange(-1)), first.combine(&second));
+    /// assert_eq!(Err(DiffError::VariantMismatch), first.combine(&ChangeData::OtherChange("Reworked".to_string())));
+    /// ```
+    pub fn combine(&self, later: &ChangeData) -> Result<ChangeData, DiffError> {
+        let (old, new) = (self, later);
+        if !ChangeData::variant_eq(old, new) {
+            return Err(DiffError::VariantMismatch)
+        }
+        if let (ChangeData::AbsoluteChange(old_data, _), ChangeData::AbsoluteChange(_, new_data)) = (old, new) {
+            Ok(ChangeData::AbsoluteChange(old_data.to_string(), new_data.to_string()))
+        } else if let (ChangeData::RelativeChange(old_data), ChangeData::RelativeChange(new_data)) = (old, new) {
+            let sum = old_data.checked_add(*new_data).ok_or(DiffError::Overflow("RelativeChange"))?;
+            Ok(ChangeData::RelativeChange(sum))
+        } else if let (Some(old_data), Some(new_data)) = (old.thousandths(), new.thousandths()) {
+            let sum = old_data.checked_add(new_data).ok_or(DiffError::Overflow("RelativeDecimalChange"))?;
+            Ok(ChangeData::RelativeDecimalChange(sum))
+        } else if let (ChangeData::ToggleChange(old_data, _), ChangeData::ToggleChange(_, new_data)) = (old, new) {
+            Ok(ChangeData::ToggleChange(*old_data, *new_data))
+        } else if let ChangeData::RemovedChange(_) = old {
+            Err(DiffError::Untracked("RemovedChange"))
+        } else {
+            Err(DiffError::Untracked("OtherChange"))
+        }
+    }
+}
+
+// Decides which properties are one property when combining patches. Properties are combined when their keys
+// are equal, so a rename-aware matcher can map an old name and a new one to the same key
+pub trait PropertyMatcher {
+    fn key(&self, property: &TreePath) -> TreePath;
+}
+
+// Only properties with the exact same path are combined, what patch_diff uses
+pub struct ExactMatch;
+
+impl PropertyMatcher for ExactMatch {
+    fn key(&self, property: &TreePath) -> TreePath {
+        property.clone()
+    }
+}
+
+impl PatchChange {
+    // The combined change is written under the newer change's property, which is the latest name of a renamed one
+    fn diff(old: &PatchChange, new: &PatchChange, track_history: bool, matcher: &dyn PropertyMatcher) -> Result<PatchChange, String>{
+        if matcher.key(&old.property) == matcher.key(&new.property) {
+            // Two entries from one patch are not consecutive steps, so chaining them is meaningless
+            if old.version == new.version {
+                return Err("PatchChange values come from the same version".to_string())
+            }
+            let mut result = PatchChange::at(new.property.clone(), &new.version, old.data.combine(&new.data).map_err(|err| err.to_string())?)
+                .with_qualifier(new.qualifier.clone());
+            result.all_levels = new.all_levels;
+            if track_history {
+                result.history = old.history.clone();
+                if result.history.is_empty() {
+                    result.history.push((old.version.to_string(), old.data.clone()));
+                }
+                result.history.push((new.version.to_string(), new.data.clone()));
+            }
+            Ok(result)
+        } else {
+            Err("PatchChange.property values do not match".to_string())
+        }
+    }
+}
+
+pub fn patch_diff(combined_patches: Vec<PatchChange>) -> Vec<PatchChange> {
+    combine_patches(combined_patches, false, ExactMatch)
+}
+
+// Same as patch_diff, but every combined change keeps the per-patch steps it was built from
+pub fn patch_diff_with_history(combined_patches: Vec<PatchChange>) -> Vec<PatchChange> {
+    combine_patches(combined_patches, true, ExactMatch)
+}
+
+// Same as patch_diff, but properties are combined whenever matcher gives them the same key
+pub fn patch_diff_matching(combined_patches: Vec<PatchChange>, matcher: impl PropertyMatcher + 'static) -> Vec<PatchChange> {
+    combine_patches(combined_patches, false, matcher)
+}
+
+fn combine_patches(combined_patches: Vec<PatchChange>, track_history: bool, matcher: impl PropertyMatcher + 'static) -> Vec<PatchChange> {
+    if combined_patches.is_empty() {
+        return vec![]
+    }
+    let mut accumulator = PatchAccumulator::new(track_history).with_matcher(matcher);
+    accumulator.add_patch(combined_patches);
+    accumulator.finish()
+}
+
+// Folds patches into a running diff one at a time, so only the combined changes are kept in memory.
+// Patches must be added oldest first to match patch_diff on all of them at once. Changes are keyed by
+// property and variant, so the output order only depends on those keys and never on the input order.
+// Changes in a section the newest patch does not have are flagged, like a past event's rewards. The main sections
+// are never flagged, as lettered patches often only touch Heroes while earlier Items changes are still in effect
+// Sections every major patch has, which are never flagged as old-only
+const MAIN_SECTIONS: [&str; 4] = ["General", "Items", "Neutral Items", "Heroes"];
+
+pub struct PatchAccumulator {
+    changes: BTreeMap<(TreePath, u8), Vec<PatchChange>>,
+    track_history: bool,
+    patch_count: usize,
+    newest_sections: BTreeSet<String>,
+    // Keeps changes of one property in the order the patch listed them instead of sorting them by text
+    document_order: bool,
+    matcher: Box<dyn PropertyMatcher>
+}
+
+impl PatchAccumulator {
+    pub fn new(track_history: bool) -> Self {
+        PatchAccumulator {
+            changes: BTreeMap::new(),
+            track_history,
+            patch_count: 0,
+            newest_sections: BTreeSet::new(),
+            document_order: false,
+            matcher: Box::new(ExactMatch)
+        }
+    }
+
+    pub fn with_document_order(mut self, document_order: bool) -> Self {
+        self.document_order = document_order;
+        self
+    }
+
+    pub fn with_matcher(mut self, matcher: impl PropertyMatcher + 'static) -> Self {
+        self.matcher = Box::new(matcher);
+        self
+    }
+
+    pub fn add_patch(&mut self, mut patch: Vec<PatchChange>) {
+        self.newest_sections = patch.iter().filter_map(|change| change.property.segments().first().cloned()).collect();
+        self.patch_count += 1;
+        if self.document_order {
+            let mut indexed: Vec<(usize, PatchChange)> = patch.into_iter().enumerate().collect();
+            indexed.sort_by(|(a_index, a), (b_index, b)| (&a.property, a_index).cmp(&(&b.property, b_index)));
+            patch = indexed.into_iter().map(|(_, change)| change).collect();
+        } else {
+            patch.sort();
+        }
+
+        for current_change in patch {
+            let key = self.matcher.key(&current_change.property);
+            let property_changes = self.changes.entry((key, current_change.data.variant_rank())).or_default();
+            let Some(previous_change) = property_changes.last_mut() else {
+                property_changes.push(current_change);
+                continue
+            };
+
+            if previous_change.version == current_change.version
+                && !matches!(current_change.data, ChangeData::OtherChange(_)) {
+                warn!("{} is listed more than once in {}, keeping each entry separately", current_change.property, current_change.version);
+            }
+
+            match PatchChange::diff(previous_change, &current_change, self.track_history, self.matcher.as_ref()) {
+                Ok(diff_value) => *previous_change = diff_value,
+                Err(_) => property_changes.push(current_change)
+            }
+        }
+    }
+
+    pub fn finish(self) -> Vec<PatchChange> {
+        let (patch_count, newest_sections) = (self.patch_count, self.newest_sections);
+        self.changes.into_values()
+            .flatten()
+            .map(|mut change| {
+                let section = change.property.segments().first();
+                change.old_only_section = patch_count > 1 && section.is_some_and(|section| {
+                    !MAIN_SECTIONS.contains(&section.as_str()) && !newest_sections.contains(section)
+                });
+                change
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DiffDelta {
+    Added(PatchChange),
+    Removed(PatchChange),
+    Changed(PatchChange, PatchChange)
+}
+
+// The changes of a patch_diff_with_history range that moved in some patch but netted out to nothing,
+// like +2 then -2. Without a recorded history the movement is unknown, so none are found
+pub fn find_reversions(range_diff: &[PatchChange]) -> Vec<&PatchChange> {
+    range_diff.iter()
+        .filter(|change| is_net_zero(&change.data) && change.history.iter().any(|(_, step)| !is_net_zero(step)))
+        .collect()
+}
+
+fn is_net_zero(data: &ChangeData) -> bool {
+    match data {
+        ChangeData::AbsoluteChange(old, new) => old == new,
+        ChangeData::RelativeChange(_) | ChangeData::RelativeDecimalChange(_) => data.thousandths() == Some(0),
+        ChangeData::ToggleChange(old, new) => old == new,
+        _ => false
+    }
+}
+
+// Numeric changes are matched by property alone, OtherChanges by property and text
+fn delta_key(change: &PatchChange) -> (String, String) {
+    match &change.data {
+        ChangeData::OtherChange(text) => (change.property.join(), text.to_string()),
+        _ => (change.property.join(), "".to_string())
+    }
+}
+
+pub fn diff_of_diffs(a: Vec<PatchChange>, b: Vec<PatchChange>) -> Vec<DiffDelta> {
+    let mut b_changes: BTreeMap<(String, String), PatchChange> = BTreeMap::new();
+    for change in b {
+        b_changes.insert(delta_key(&change), change);
+    }
+
+    let mut deltas: BTreeMap<(String, String), DiffDelta> = BTreeMap::new();
+    for a_change in a {
+        let key = delta_key(&a_change);
+        match b_changes.remove(&key) {
+            Some(b_change) => {
+                if a_change.data != b_change.data {
+                    deltas.insert(key, DiffDelta::Changed(a_change, b_change));
+                }
+            },
+            None => {
+                deltas.insert(key, DiffDelta::Removed(a_change));
+            }
+        }
+    }
+    for (key, b_change) in b_changes {
+        deltas.insert(key, DiffDelta::Added(b_change));
+    }
+    deltas.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{ChangeData, DiffDelta, ExactMatch, PatchAccumulator, PatchChange, PropertyMatcher, TreePath, TextOptions, Verdict, classify_change, patch_diff, patch_diff_with_history, diff_of_diffs, find_reversions, patch_diff_matching};
+
+    #[test]
+    fn absolute_diff_works() {
+        let old_change = ChangeData::AbsoluteChange("A".to_string(), "B".to_string());
+        let new_change = ChangeData::AbsoluteChange("B".to_string(), "C".to_string());
+        let result = old_change.combine(&new_change).unwrap();
+        assert_eq!(ChangeData::AbsoluteChange("A".to_string(), "C".to_string()), result)
+    }
+
+    #[test]
+    fn relative_diff_works() {
+        let old_change = ChangeData::RelativeChange(1);
+        let new_change = ChangeData::RelativeChange(2);
+        let result = old_change.combine(&new_change).unwrap();
+        assert_eq!(ChangeData::RelativeChange(3), result)
+    }
+
+    #[test]
+    fn other_diff_fails() {
+        let old_change = ChangeData::OtherChange("Change 1".to_string());
+        let new_change = ChangeData::OtherChange("Change 2".to_string());
+        let result = old_change.combine(&new_change).err().unwrap().to_string();
+        assert_eq!("ChangeData::OtherChange does not track diff".to_string(), result)
+    }
+
+    #[test]
+    fn different_variants_fail_diff() {
+        let old_change = ChangeData::AbsoluteChange("A".to_string(), "B".to_string());
+        let new_change = ChangeData::RelativeChange(10);
+        let result = old_change.combine(&new_change).err().unwrap().to_string();
+        assert_eq!("ChangeData variants are not equal".to_string(), result)
+    }
+
+    #[test]
+    fn same_property_diff_works() {
+        let old_property = "Items > Blade Mail > Duration".to_string();
+        let old_patch_name = "7.32".to_string();
+        let old_data = ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string());
+        let old_change = PatchChange::new(&old_property, &old_patch_name, old_data);
+
+        let new_data = ChangeData::AbsoluteChange("5.5s".to_string(), "6.5s".to_string());
+        let new_patch_name = "7.32a".to_string();
+        let new_change = PatchChange::new(&old_property, &new_patch_name, new_data);
+
+        let result = PatchChange::diff(&old_change, &new_change, false, &ExactMatch).unwrap();
+
+        assert_eq!(PatchChange::new(&old_property, &new_patch_name, ChangeData::AbsoluteChange("4.5s".to_string(), "6.5s".to_string())), result)
+    }
+
+    #[test]
+    fn different_properties_fail_diff() {
+        let old_property = "Items > Blade Mail > Duration".to_string();
+        let old_patch_name = "7.32".to_string();
+        let old_data = ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string());
+        let old_change = PatchChange::new(&old_property, &old_patch_name, old_data);
+
+        let new_property = "Items > Black King Bar > Duration".to_string();
+        let new_patch_name = "7.32a".to_string();
+        let new_data = ChangeData::AbsoluteChange("5.5s".to_string(), "6.5s".to_string());
+        let new_change = PatchChange::new(&new_property, &new_patch_name, new_data);
+
+        let result = PatchChange::diff(&old_change, &new_change, false, &ExactMatch).err().unwrap();
+
+        assert_eq!("PatchChange.property values do not match".to_string(), result)
+    }
+
+    #[test]
+    fn patch_diff_works() {
+        let old_patch_name = "7.32".to_string();
+        let old_change_1 = PatchChange::new(
+            "Items > Blade Mail > Duration",
+            &old_patch_name,
+            ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string()));
+        let old_change_2 = PatchChange::new(
+            "Items > Blade Mail > Armor",
+            &old_patch_name,
+            ChangeData::AbsoluteChange("4".to_string(), "5".to_string()));
+        let old_change_3 = PatchChange::new(
+            "Heroes > Zeus > Base Armor",
+            &old_patch_name,
+            ChangeData::RelativeChange(2));
+        let old_change_4 = PatchChange::new(
+            "Heroes > Zeus",
+            &old_patch_name,
+            ChangeData::OtherChange("Something random".to_string()));
+        let mut old_patch = vec![old_change_1, old_change_2, old_change_3, old_change_4];
+
+        let new_patch_name = "7.32a".to_string();
+        let new_change_1 = PatchChange::new(
+            "Items > Blade Mail > Duration",
+            &new_patch_name,
+            ChangeData::AbsoluteChange("5.5s".to_string(), "6.5s".to_string()));
+        let new_change_2 = PatchChange::new(
+            "Items > Blade Mail > Damage",
+            &new_patch_name,
+            ChangeData::AbsoluteChange("11".to_string(), "60".to_string()));
+        let new_change_3 = PatchChange::new(
+            "Heroes > Zeus > Base Armor",
+            &new_patch_name,
+            ChangeData::RelativeChange(1));
+        let new_change_4 = PatchChange::new(
+            "Heroes > Crystal Maiden",
+            &new_patch_name,
+            ChangeData::OtherChange("Auto-attacks now take priority when determining kill credit".to_string()));
+        let mut new_patch = vec![new_change_1, new_change_2, new_change_3, new_change_4];
+
+        old_patch.append(&mut new_patch);
+        let result = patch_diff(old_patch);
+
+        assert_eq!(PatchChange::new(
+            "Items > Blade Mail > Duration",
+            "7.32a",
+            ChangeData::AbsoluteChange("4.5s".to_string(), "6.5s".to_string())), result[5])
+    }
+
+    #[test]
+    fn decimal_and_whole_rel_num_combine() {
+        let result = patch_diff(vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Base armor decreased by 0.25", "Heroes > Zeus", "7.32b"),
+            PatchChange::parse_text("Base armor increased by 1.5", "Heroes > Zeus", "7.32c")
+        ]);
+
+        assert_eq!(1, result.len());
+        assert_eq!(ChangeData::RelativeDecimalChange(2250), result[0].data);
+        assert_eq!("Heroes > Zeus > Base armor increased by 2.25".to_string(), result[0].write_text())
+    }
+
+    #[test]
+    fn overflowing_rel_num_sum_is_kept_apart() {
+        let result = patch_diff(vec![
+            PatchChange::parse_text("Base armor increased by 2147483647", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32b")
+        ]);
+
+        assert_eq!(2, result.len());
+        assert_eq!("Heroes > Zeus > Base armor increased by 2147483647".to_string(), result[0].write_text())
+    }
+
+    #[test]
+    fn diff_of_diffs_works() {
+        let a = vec![
+            PatchChange::new(
+                "Items > Blade Mail > Duration",
+                "7.32a",
+                ChangeData::AbsoluteChange("4.5s".to_string(), "6.5s".to_string())),
+            PatchChange::new(
+                "Heroes > Zeus > Base Armor",
+                "7.32a",
+                ChangeData::RelativeChange(3)),
+            PatchChange::new(
+                "Heroes > Zeus",
+                "7.32a",
+                ChangeData::OtherChange("Something random".to_string()))
+        ];
+        let b = vec![
+            PatchChange::new(
+                "Items > Blade Mail > Duration",
+                "7.32b",
+                ChangeData::AbsoluteChange("4.5s".to_string(), "6.5s".to_string())),
+            PatchChange::new(
+                "Heroes > Zeus > Base Armor",
+                "7.32b",
+                ChangeData::RelativeChange(1)),
+            PatchChange::new(
+                "Heroes > Zeus",
+                "7.32b",
+                ChangeData::OtherChange("Something else".to_string()))
+        ];
+        let result = diff_of_diffs(a, b);
+
+        assert_eq!(vec![
+            DiffDelta::Added(PatchChange::new(
+                "Heroes > Zeus",
+                "7.32b",
+                ChangeData::OtherChange("Something else".to_string()))),
+            DiffDelta::Removed(PatchChange::new(
+                "Heroes > Zeus",
+                "7.32a",
+                ChangeData::OtherChange("Something random".to_string()))),
+            DiffDelta::Changed(
+                PatchChange::new(
+                    "Heroes > Zeus > Base Armor",
+                    "7.32a",
+                    ChangeData::RelativeChange(3)),
+                PatchChange::new(
+                    "Heroes > Zeus > Base Armor",
+                    "7.32b",
+                    ChangeData::RelativeChange(1)))
+        ], result)
+    }
+
+    #[test]
+    fn patch_diff_history_works() {
+        let property = "Items > Blade Mail > Duration".to_string();
+        let old_change = PatchChange::new(
+            &property,
+            "7.32",
+            ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string()));
+        let new_change = PatchChange::new(
+            &property,
+            "7.32a",
+            ChangeData::AbsoluteChange("5.5s".to_string(), "6.5s".to_string()));
+        let result = patch_diff_with_history(vec![old_change, new_change]);
+
+        assert_eq!(1, result.len());
+        assert_eq!(vec![
+            ("7.32".to_string(), ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string())),
+            ("7.32a".to_string(), ChangeData::AbsoluteChange("5.5s".to_string(), "6.5s".to_string()))
+        ], result[0].history);
+        assert_eq!(
+            "Items > Blade Mail > Duration increased from 4.5s to 6.5s (7.32: 4.5s → 5.5s, 7.32a: 5.5s → 6.5s)".to_string(),
+            result[0].write_text_with(&TextOptions { history: true, ..Default::default() })
+        )
+    }
+
+    #[test]
+    fn patch_diff_empty_works() {
+        assert!(patch_diff(vec![]).is_empty());
+        assert!(patch_diff_with_history(vec![]).is_empty())
+    }
+
+    #[test]
+    fn patch_diff_skips_history_by_default() {
+        let property = "Heroes > Zeus > Base armor".to_string();
+        let old_change = PatchChange::new(&property, "7.32", ChangeData::RelativeChange(1));
+        let new_change = PatchChange::new(&property, "7.32a", ChangeData::RelativeChange(2));
+        let result = patch_diff(vec![old_change, new_change]);
+
+        assert!(result[0].history.is_empty())
+    }
+
+    #[test]
+    fn same_version_duplicates_are_kept() {
+        let property = "Items > Blade Mail > Duration".to_string();
+        let first_change = PatchChange::new(
+            &property,
+            "7.32",
+            ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string()));
+        let second_change = PatchChange::new(
+            &property,
+            "7.32",
+            ChangeData::AbsoluteChange("5s".to_string(), "6s".to_string()));
+        let result = PatchChange::diff(&first_change, &second_change, false, &ExactMatch).err().unwrap();
+        assert_eq!("PatchChange values come from the same version".to_string(), result);
+
+        let result = patch_diff(vec![first_change, second_change]);
+        assert_eq!(vec![
+            PatchChange::new(&property, "7.32", ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string())),
+            PatchChange::new(&property, "7.32", ChangeData::AbsoluteChange("5s".to_string(), "6s".to_string()))
+        ], result)
+    }
+
+    #[test]
+    fn opposite_toggles_cancel() {
+        let result = patch_diff(vec![
+            PatchChange::parse_text("Can now be cast while rooted", "Heroes > Zeus > Arc Lightning", "7.32"),
+            PatchChange::parse_text("Can no longer be cast while rooted", "Heroes > Zeus > Arc Lightning", "7.32b")
+        ]);
+
+        assert_eq!(1, result.len());
+        assert!(result[0].is_unchanged());
+        assert_eq!(Verdict::Neutral, classify_change(&result[0]))
+    }
+
+    #[test]
+    fn accumulated_patches_match_patch_diff() {
+        let old_patch = vec![
+            PatchChange::parse_text("Duration increased from 4.5s to 5.5s", "Items > Blade Mail".to_string(), "7.32"),
+            PatchChange::parse_text("Base armor increased by 2", "Heroes > Zeus".to_string(), "7.32"),
+            PatchChange::parse_text("Something random", "Heroes > Zeus".to_string(), "7.32")
+        ];
+        let new_patch = vec![
+            PatchChange::parse_text("Duration increased from 5.5s to 6.5s", "Items > Blade Mail".to_string(), "7.32b"),
+            PatchChange::parse_text("Base armor decreased by 1", "Heroes > Zeus".to_string(), "7.32b"),
+            PatchChange::parse_text("Damage increased from 11 to 60", "Items > Blade Mail".to_string(), "7.32b")
+        ];
+
+        let mut accumulator = PatchAccumulator::new(false);
+        accumulator.add_patch(old_patch.clone());
+        accumulator.add_patch(new_patch.clone());
+
+        let mut all_changes = old_patch;
+        all_changes.extend(new_patch);
+        assert_eq!(patch_diff(all_changes), accumulator.finish())
+    }
+
+    #[test]
+    fn patch_diff_ignores_input_order() {
+        let tree_location = "Heroes > Zeus".to_string();
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Something random", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Cooldown decreased from 90 to 80", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Something else", tree_location.clone(), "7.32b"),
+            PatchChange::parse_text("Base armor increased by 2", tree_location.clone(), "7.32b"),
+            PatchChange::parse_text("Cooldown decreased from 80 to 70", tree_location, "7.32b")
+        ];
+        let mut shuffled = changes.clone();
+        shuffled.reverse();
+        shuffled.rotate_left(2);
+
+        let expected: Vec<String> = patch_diff(changes).iter().map(|change| change.write_text()).collect();
+        let result: Vec<String> = patch_diff(shuffled).iter().map(|change| change.write_text()).collect();
+        assert_eq!(expected, result)
+    }
+
+    #[test]
+    fn document_order_keeps_other_changes_in_place() {
+        let patch = vec![
+            PatchChange::parse_text("Zeus now shouts", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Arcs are brighter", "Heroes > Zeus", "7.32")
+        ];
+
+        let mut sorted = PatchAccumulator::new(false);
+        sorted.add_patch(patch.clone());
+        let mut in_order = PatchAccumulator::new(false).with_document_order(true);
+        in_order.add_patch(patch);
+
+        let texts = |changes: Vec<PatchChange>| changes.iter()
+            .filter(|change| matches!(change.data, ChangeData::OtherChange(_)))
+            .map(|change| change.write_text_line())
+            .collect::<Vec<String>>();
+        assert_eq!(vec!["Arcs are brighter".to_string(), "Zeus now shouts".to_string()], texts(sorted.finish()));
+        assert_eq!(vec!["Zeus now shouts".to_string(), "Arcs are brighter".to_string()], texts(in_order.finish()))
+    }
+
+    #[test]
+    fn old_only_section_is_flagged() {
+        let mut accumulator = PatchAccumulator::new(false);
+        accumulator.add_patch(vec![
+            PatchChange::parse_text("Reward increased from 10 to 20", "Frostivus > Rewards", "7.32"),
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32")
+        ]);
+        accumulator.add_patch(vec![PatchChange::parse_text("Base armor increased by 1", "Heroes > Lina", "7.32b")]);
+        let result = accumulator.finish();
+
+        let flagged: Vec<(String, bool)> = result.iter().map(|change| (change.write_text(), change.is_old_only_section())).collect();
+        assert_eq!(vec![
+            ("Frostivus > Rewards > Reward increased from 10 to 20".to_string(), true),
+            ("Heroes > Lina > Base armor increased by 1".to_string(), false),
+            ("Heroes > Zeus > Base armor increased by 1".to_string(), false)
+        ], flagged);
+        assert!(patch_diff(result).iter().all(|change| !change.is_old_only_section()))
+    }
+
+    #[test]
+    fn main_section_missing_later_is_not_flagged() {
+        let mut accumulator = PatchAccumulator::new(false);
+        accumulator.add_patch(vec![
+            PatchChange::parse_text("Cooldown decreased from 90 to 80", "Items > Blink Dagger", "7.32"),
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32")
+        ]);
+        accumulator.add_patch(vec![PatchChange::parse_text("Base armor increased by 1", "Heroes > Lina", "7.32a")]);
+        accumulator.add_patch(vec![PatchChange::parse_text("Base armor increased by 1", "Heroes > Axe", "7.32b")]);
+
+        assert!(accumulator.finish().iter().all(|change| !change.is_old_only_section()))
+    }
+
+    #[test]
+    fn reverted_change_is_found() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 2", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Base armor decreased by 2", "Heroes > Zeus", "7.32a"),
+            PatchChange::parse_text("Cooldown decreased from 90 to 80", "Heroes > Zeus > Arc Lightning", "7.32"),
+            PatchChange::parse_text("Cooldown increased from 80 to 90", "Heroes > Zeus > Arc Lightning", "7.32b"),
+            PatchChange::parse_text("Damage increased by 1", "Heroes > Zeus > Arc Lightning", "7.32"),
+            PatchChange::parse_text("Damage increased by 1", "Heroes > Zeus > Arc Lightning", "7.32a")
+        ];
+        let result = patch_diff_with_history(changes.to_vec());
+        let reverted: Vec<String> = find_reversions(&result).iter().map(|change| change.property().join()).collect();
+
+        assert_eq!(vec!["Heroes > Zeus > Arc Lightning > Cooldown".to_string(), "Heroes > Zeus > Base armor".to_string()], reverted);
+        assert!(find_reversions(&patch_diff(changes)).is_empty())
+    }
+
+    #[test]
+    fn custom_matcher_combines_renamed_properties() {
+        struct BaseDamage;
+        impl PropertyMatcher for BaseDamage {
+            fn key(&self, property: &TreePath) -> TreePath {
+                TreePath::from(property.join().replace("Base damage", "Damage"))
+            }
+        }
+        let changes = vec![
+            PatchChange::parse_text("Base damage increased by 2", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Damage increased by 3", "Heroes > Zeus", "7.32a")
+        ];
+
+        assert_eq!(2, patch_diff(changes.to_vec()).len());
+        assert_eq!(vec!["Heroes > Zeus > Damage increased by 5".to_string()],
+            patch_diff_matching(changes, BaseDamage).iter().map(|change| change.write_text()).collect::<Vec<String>>())
+    }
+}
@@ -0,0 +1,881 @@
+use regex::Regex;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+mod change;
+mod diff;
+mod parse;
+
+pub use change::{ChangeData, PatchChange, TextOptions, TreePath};
+pub use diff::{DiffDelta, DiffError, ExactMatch, PatchAccumulator, PropertyMatcher, diff_of_diffs, find_reversions, patch_diff, patch_diff_matching, patch_diff_with_history};
+
+// Stands in for the old side of an AbsoluteChange when only the new value was given
+pub const UNKNOWN_VALUE: &str = "?";
+// Between the levels of a property path when it is written as text
+pub const SEP: &str = " > ";
+// Written after a scalar change of a leveled ability that the notes say applies to every level
+const ALL_LEVELS: &str = "at all levels";
+const DECIMAL_SCALE: i64 = 1000;
+pub const NUMBER_STR: &str = r"-?(?:\d{1,3}(?:,\d{3})+(?:\.\d+)?|\d*\.?\d+)";
+pub const RANGE_STR: &str = r"^(\d*\.?\d+)-(\d*\.?\d+)\D*$";
+pub const FRACTION_STR: &str = r"(\d*\.?\d+) / (\d*\.?\d+)";
+// Numeric per-level values, optionally carrying a unit on each level
+pub const UNIT_STR: &str = r"^(?:\d*\.?\d+(?:s|%)?/)*\d*\.?\d+(s|%)?$";
+// Units implied by a property name, e.g. "Duration increased by 2" means seconds
+const UNIT_KEYWORDS: [(&str, &str); 3] = [("attack time", "s"), ("duration", "s"), ("cooldown", "s")];
+// Splits "Talent > Level 10 Talent" around the level number
+const TALENT_LEVEL_STR: &str = r"^(.*Level )(\d+)( Talent.*)$";
+// Absolute values usually carry their own unit, except for these properties
+const BARE_UNIT_KEYWORDS: [&str; 1] = ["attack time"];
+// Unit words written after a number, dropped before it is read
+const NUMERIC_UNITS: [&str; 4] = ["gold", "HP", "MP", "s"];
+
+// Properties below the entity (e.g. "Base armor" or "Talent > Level 10 Talent") starting with a keyword
+// in `first` are moved ahead of the rest, and ones matching `last` behind it, in the order listed
+#[derive(Debug, Default)]
+pub struct OutputOrder {
+    pub first: Vec<String>,
+    pub last: Vec<String>
+}
+
+impl OutputOrder {
+    fn rank(&self, sub_property: &str) -> usize {
+        if let Some(idx) = self.first.iter().position(|keyword| sub_property.starts_with(keyword.as_str())) {
+            return idx
+        }
+        match self.last.iter().position(|keyword| sub_property.starts_with(keyword.as_str())) {
+            Some(idx) => self.first.len() + 1 + idx,
+            None => self.first.len()
+        }
+    }
+}
+
+// Orders changes by section, then entity, then the OutputOrder priority, then the remaining property path
+pub fn sort_for_output(changes: &mut [PatchChange], order: &OutputOrder) {
+    changes.sort_by_cached_key(|change| {
+        let segments = change.property.segments();
+        let section = segments.first().cloned().unwrap_or_default();
+        let entity = segments.get(1).cloned().unwrap_or_default();
+        let sub_property = segments.get(2..).unwrap_or_default().join(SEP);
+        (section, entity, order.rank(&sub_property), talent_sort_key(&sub_property))
+    });
+}
+
+pub fn talent_level(property: &str) -> Option<u32> {
+    let talent_match = Regex::new(TALENT_LEVEL_STR).unwrap();
+    talent_match.captures(property)?[2].parse().ok()
+}
+
+// Talents are ordered by their level as a number, everything else by the property path
+fn talent_sort_key(sub_property: &str) -> (String, u32, String) {
+    let talent_match = Regex::new(TALENT_LEVEL_STR).unwrap();
+    match talent_match.captures(sub_property) {
+        Some(capture_groups) => (
+            capture_groups[1].to_string(),
+            capture_groups[2].parse().unwrap_or(0),
+            capture_groups[3].to_string()
+        ),
+        None => (sub_property.to_string(), 0, "".to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Buff,
+    Nerf,
+    Neutral,
+    // Only for a whole entity, as many buffs as nerfs
+    Mixed
+}
+
+// How the html output is grouped instead of by its section, entity and ability headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Section,
+    Entity,
+    Verdict
+}
+
+// Property keywords where a smaller value is the better one
+const LOWER_IS_BETTER: [&str; 6] = ["cooldown", "cost", "attack time", "restore time", "cast point", "delay"];
+
+// "Level 10 Talent +20 Damage replaced with +25 Damage" is kept under the talent itself, while numeric changes
+// to a talent like "Level 10 Talent Fissure Damage increased from 10 to 15" are properties under it
+fn is_talent_swap(property: &TreePath) -> bool {
+    property.last().ends_with("Talent")
+}
+
+pub fn classify_change(change: &PatchChange) -> Verdict {
+    let property_name = change.property.last().to_lowercase();
+    let lower_is_better = LOWER_IS_BETTER.iter().any(|keyword| property_name.contains(keyword));
+
+    let direction = match &change.data {
+        ChangeData::AbsoluteChange(old, new) => {
+            if is_talent_swap(&change.property) {
+                return Verdict::Neutral
+            }
+            absolute_change_direction(old, new)
+        },
+        ChangeData::RelativeChange(_) | ChangeData::RelativeDecimalChange(_) => {
+            let value = change.data.thousandths().unwrap();
+            if value > 0 {
+                "increased".to_string()
+            } else if value < 0 {
+                "decreased".to_string()
+            } else {
+                return Verdict::Neutral
+            }
+        },
+        ChangeData::ToggleChange(old, new) if old != new => {
+            return if *new { Verdict::Buff } else { Verdict::Nerf }
+        },
+        ChangeData::ToggleChange(_, _) | ChangeData::RemovedChange(_) | ChangeData::OtherChange(_) => return Verdict::Neutral
+    };
+
+    match (direction.as_str(), lower_is_better) {
+        ("increased", false) | ("decreased", true) => Verdict::Buff,
+        ("increased", true) | ("decreased", false) => Verdict::Nerf,
+        _ => Verdict::Neutral
+    }
+}
+
+// (entity, net verdict, buffs, nerfs) for every "Section > Entity" in the diff, in path order
+pub fn entity_verdicts(changes: &[PatchChange]) -> Vec<(String, Verdict, usize, usize)> {
+    let mut counts: BTreeMap<(String, String), (usize, usize)> = BTreeMap::new();
+    for change in changes {
+        let segments = change.property.segments();
+        let (Some(section), Some(entity)) = (segments.first(), segments.get(1)) else { continue };
+        let (buffs, nerfs) = counts.entry((section.to_string(), entity.to_string())).or_default();
+        match classify_change(change) {
+            Verdict::Buff => *buffs += 1,
+            Verdict::Nerf => *nerfs += 1,
+            _ => {}
+        }
+    }
+    counts.into_iter()
+        .map(|((_, entity), (buffs, nerfs))| {
+            let verdict = match buffs.cmp(&nerfs) {
+                Ordering::Greater => Verdict::Buff,
+                Ordering::Less => Verdict::Nerf,
+                Ordering::Equal if buffs > 0 => Verdict::Mixed,
+                Ordering::Equal => Verdict::Neutral
+            };
+            (entity, verdict, buffs, nerfs)
+        })
+        .collect()
+}
+
+// Splits the changes into named groups, keeping their order within each group. Sections and entities are
+// named by their path and come in the order they first appear, verdicts as buffs, nerfs and then the rest
+pub fn regroup(changes: Vec<PatchChange>, mode: GroupBy) -> Vec<(String, Vec<PatchChange>)> {
+    let mut groups: Vec<(String, Vec<PatchChange>)> = match mode {
+        GroupBy::Verdict => ["Buffs", "Nerfs", "Other"].iter().map(|name| (name.to_string(), vec![])).collect(),
+        _ => vec![]
+    };
+    for change in changes {
+        let name = match mode {
+            GroupBy::Section => change.property.segments()[..1.min(change.property.segments().len())].join(SEP),
+            GroupBy::Entity => change.property.segments()[..2.min(change.property.segments().len())].join(SEP),
+            GroupBy::Verdict => match classify_change(&change) {
+                Verdict::Buff => "Buffs",
+                Verdict::Nerf => "Nerfs",
+                _ => "Other"
+            }.to_string()
+        };
+        match groups.iter_mut().find(|(group, _)| *group == name) {
+            Some((_, group_changes)) => group_changes.push(change),
+            None => groups.push((name, vec![change]))
+        }
+    }
+    groups.retain(|(_, group_changes)| !group_changes.is_empty());
+    groups
+}
+
+// Every property path changed in the diff, e.g. to spot one stat named two ways
+pub fn distinct_properties(changes: &[PatchChange]) -> BTreeSet<String> {
+    changes.iter().map(|change| change.property.join()).collect()
+}
+
+// Drops the prose OtherChanges, keeping only changes with parsed values
+pub fn strip_other(changes: Vec<PatchChange>) -> Vec<PatchChange> {
+    changes.into_iter().filter(|change| !matches!(change.data, ChangeData::OtherChange(_))).collect()
+}
+
+// Changes to properties that had no value before, like "Now has a 50 Mana Cost", whose old value is 0 at every level
+pub fn only_additions(changes: Vec<PatchChange>) -> Vec<PatchChange> {
+    changes.into_iter()
+        .filter(|change| match &change.data {
+            ChangeData::AbsoluteChange(old, _) => split_levels(old).iter().all(|value| extract_number(value) == Some(0.0)),
+            _ => false
+        })
+        .collect()
+}
+
+// Drops numeric changes moving less than min, where an absolute change is kept when either its difference
+// or its percent change reaches min. Changes without a single number on each side are always kept
+pub fn filter_by_magnitude(changes: Vec<PatchChange>, min: f32) -> Vec<PatchChange> {
+    changes.into_iter()
+        .filter(|change| match &change.data {
+            ChangeData::AbsoluteChange(old, new) => match (parse_numeric_value(old), parse_numeric_value(new)) {
+                (Some(old_number), Some(new_number)) => {
+                    (new_number - old_number).abs() >= min || change.data.percent_delta().is_some_and(|percent| percent.abs() >= min)
+                },
+                _ => true
+            },
+            ChangeData::RelativeChange(_) | ChangeData::RelativeDecimalChange(_) => {
+                change.data.thousandths().is_some_and(|value| value.abs() as f32 / DECIMAL_SCALE as f32 >= min)
+            },
+            _ => true
+        })
+        .collect()
+}
+
+// Absolute changes that end on the value they started from, where numbers within epsilon at every level count as
+// the same value. An epsilon of 0 only drops changes whose old and new text are identical
+pub fn drop_unchanged_values(changes: Vec<PatchChange>, epsilon: f32) -> Vec<PatchChange> {
+    changes.into_iter()
+        .filter(|change| match &change.data {
+            ChangeData::AbsoluteChange(old, new) => !values_match(old, new, epsilon),
+            _ => true
+        })
+        .collect()
+}
+
+fn values_match(old: &str, new: &str, epsilon: f32) -> bool {
+    if old == new {
+        return true
+    }
+    // Only plain numbers like "1.7s/2s" are compared, text around a number ("+15 Damage") can change on its own
+    let number_match = Regex::new(UNIT_STR).unwrap();
+    let (old_values, new_values) = (split_levels(old), split_levels(new));
+    if epsilon <= 0.0 || old_values.len() != new_values.len() || !number_match.is_match(old) || !number_match.is_match(new) {
+        return false
+    }
+    old_values.iter().zip(new_values).all(|(old_value, new_value)| {
+        match (extract_number(old_value), extract_number(new_value)) {
+            (Some(old_number), Some(new_number)) => (old_number - new_number).abs() <= epsilon,
+            _ => false
+        }
+    })
+}
+
+// Drops changes whose property path matches any of the patterns, each a regex or a plain substring
+pub fn exclude_changes(changes: Vec<PatchChange>, patterns: &[String]) -> Vec<PatchChange> {
+    let excluded: Vec<Regex> = patterns.iter()
+        .map(|pattern| Regex::new(pattern).unwrap_or_else(|_| Regex::new(&regex::escape(pattern)).unwrap()))
+        .collect();
+    changes.into_iter()
+        .filter(|change| {
+            let property = change.property.join();
+            !excluded.iter().any(|pattern| pattern.is_match(&property))
+        })
+        .collect()
+}
+
+// How many changes fell through every pattern and were kept as OtherChange
+#[derive(Debug, PartialEq)]
+pub struct ParseReport {
+    pub total: usize,
+    pub unparsed: usize
+}
+
+impl ParseReport {
+    pub fn from_changes(changes: &[PatchChange]) -> Self {
+        let unparsed = changes.iter().filter(|change| matches!(change.data, ChangeData::OtherChange(_))).count();
+        ParseReport { total: changes.len(), unparsed }
+    }
+
+    pub fn unparsed_percent(&self) -> f32 {
+        if self.total == 0 {
+            return 0.0
+        }
+        100.0 * self.unparsed as f32 / self.total as f32
+    }
+
+    // False when more than threshold percent of the changes could not be parsed
+    pub fn passes(&self, threshold: f32) -> bool {
+        self.unparsed_percent() <= threshold
+    }
+}
+
+// Counts over a whole diff, written at the top of a DiffReport
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DiffSummary {
+    pub total: usize,
+    pub unparsed: usize,
+    pub buffs: usize,
+    pub nerfs: usize
+}
+
+impl DiffSummary {
+    pub fn from_changes(changes: &[PatchChange]) -> Self {
+        let count = |verdict: Verdict| changes.iter().filter(|change| classify_change(change) == verdict).count();
+        DiffSummary {
+            total: changes.len(),
+            unparsed: ParseReport::from_changes(changes).unparsed,
+            buffs: count(Verdict::Buff),
+            nerfs: count(Verdict::Nerf)
+        }
+    }
+}
+
+// A diff together with the range it covers and when it was made, so its JSON describes itself.
+// This is what --format json writes, with each change serialized the way render.rs writes it
+#[derive(Debug, Serialize)]
+pub struct DiffReport {
+    pub old: String,
+    pub new: String,
+    // Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub changes: Vec<PatchChange>,
+    pub summary: DiffSummary
+}
+
+impl DiffReport {
+    pub fn new(old: &str, new: &str, changes: Vec<PatchChange>) -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+        DiffReport { old: old.to_string(), new: new.to_string(), timestamp, summary: DiffSummary::from_changes(&changes), changes }
+    }
+}
+
+// Exit code for a diff with nothing to show, so scripts can tell it apart from success and errors
+pub const NO_CHANGES_EXIT_CODE: i32 = 2;
+
+// The exit code and message when none of the changes would be written, None when some would
+pub fn no_changes_exit(changes: &[PatchChange], options: &TextOptions, old: &str, new: &str) -> Option<(i32, String)> {
+    if changes.iter().any(|change| options.show_reverted || !change.is_unchanged()) {
+        return None
+    }
+    Some((NO_CHANGES_EXIT_CODE, format!("No changes between {} and {}", old, new)))
+}
+
+// 150 -> "0.15", 2000 -> "2"
+pub(crate) fn write_thousandths(value: i64) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let (whole, fraction) = (value.abs() / DECIMAL_SCALE, value.abs() % DECIMAL_SCALE);
+    if fraction == 0 {
+        return format!("{}{}", sign, whole)
+    }
+    format!("{}{}.{}", sign, whole, format!("{:03}", fraction).trim_end_matches('0'))
+}
+
+pub(crate) fn write_signed_thousandths(value: i64) -> String {
+    if value < 0 { write_thousandths(value) } else { format!("+{}", write_thousandths(value)) }
+}
+
+// A bare "/" separates per-level values, while a spaced " / " is a fraction within one value
+fn split_levels(value: &str) -> Vec<&str> {
+    if value.contains(" / ") {
+        vec![value]
+    } else {
+        value.split('/').collect()
+    }
+}
+
+// The single value shared by every level, if a multi-level value has no variation
+fn uniform_value(value: &str) -> Option<&str> {
+    let levels = split_levels(value);
+    if levels.len() > 1 && levels.iter().all(|level| level == &levels[0]) {
+        Some(levels[0])
+    } else {
+        None
+    }
+}
+
+fn infer_unit(property: &str, value: &str) -> Option<String> {
+    let unit_match = Regex::new(UNIT_STR).unwrap();
+    let capture_groups = unit_match.captures(value)?;
+    if let Some(unit) = capture_groups.get(1) {
+        return Some(unit.as_str().to_string())
+    }
+
+    let property_name = property.rsplit(SEP).next().unwrap().to_lowercase();
+    if !BARE_UNIT_KEYWORDS.iter().any(|keyword| property_name.contains(keyword)) {
+        return None
+    }
+    unit_for_property(property).map(|unit| unit.to_string())
+}
+
+pub fn unit_for_property(property: &str) -> Option<&'static str> {
+    let property_name = property.rsplit(SEP).next().unwrap().to_lowercase();
+    UNIT_KEYWORDS.iter()
+        .find(|(keyword, _)| property_name.contains(keyword))
+        .map(|(_, unit)| *unit)
+}
+
+// Writes the unit after a numeric value that was given without one, so "1.7" and "1.6s" read the same
+fn with_unit(value: &str, unit: &Option<String>) -> String {
+    let unit_match = Regex::new(UNIT_STR).unwrap();
+    let is_bare_number = unit_match.captures(value).is_some_and(|capture_groups| capture_groups.get(1).is_none());
+    match unit {
+        Some(unit) if is_bare_number => format!("{}{}", value, unit),
+        _ => value.to_string()
+    }
+}
+
+fn extract_range(value: &str) -> Option<(f32, f32)> {
+    let range_match = Regex::new(RANGE_STR).unwrap();
+    let capture_groups = range_match.captures(value)?;
+    Some((parse_numeric_value(&capture_groups[1])?, parse_numeric_value(&capture_groups[2])?))
+}
+
+// Reads a single number like "1,000 gold", "-15%" or "2.5s", None when anything else is left around it
+pub fn parse_numeric_value(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let value = value.strip_suffix('%').unwrap_or(value);
+    let value = NUMERIC_UNITS.iter()
+        .find_map(|unit| value.strip_suffix(unit))
+        .map(str::trim_end)
+        .unwrap_or(value);
+    let whole_number = Regex::new(&format!("^{}$", NUMBER_STR)).unwrap();
+    if !whole_number.is_match(value) {
+        return None
+    }
+    value.replace(',', "").parse::<f32>().ok()
+}
+
+fn extract_number(value: &str) -> Option<f32> {
+    // A range like "200-300" stands for its midpoint
+    if let Some((low, high)) = extract_range(value) {
+        return Some((low + high) / 2.0)
+    }
+
+    let fraction_match = Regex::new(FRACTION_STR).unwrap();
+    if let Some(capture_groups) = fraction_match.captures(value) {
+        let numerator = parse_numeric_value(capture_groups.get(1).unwrap().as_str())?;
+        let denominator = parse_numeric_value(capture_groups.get(2).unwrap().as_str())?;
+        if denominator == 0.0 {
+            return None
+        }
+        return Some(numerator / denominator)
+    }
+
+    if let Some(number) = parse_numeric_value(value) {
+        return Some(number)
+    }
+    let num_match = Regex::new(NUMBER_STR).unwrap();
+    parse_numeric_value(num_match.find(value)?.as_str())
+}
+
+fn absolute_change_direction(old: &str, new: &str) -> String {
+    let old_values = split_levels(old);
+    let new_values = split_levels(new);
+    let mut longest_len = old_values.len();
+
+    if new_values.len() > longest_len {
+        longest_len = new_values.len()
+    }
+
+    let mut i = 0;
+
+    let mut is_inc = false;
+    let mut is_dec = false;
+
+    while i < longest_len {
+        let mut old_str = old_values[old_values.len() - 1];
+        if i < old_values.len() {
+            old_str = old_values[i];
+        }
+        let mut new_str = new_values[new_values.len() - 1];
+        if i < new_values.len() {
+            new_str = new_values[i];
+        }
+        i += 1;
+
+        // Two ranges move in both endpoints, e.g. "200-300" to "250-350" is increased
+        let comparisons = match (extract_range(old_str), extract_range(new_str)) {
+            (Some((old_low, old_high)), Some((new_low, new_high))) => vec![(old_low, new_low), (old_high, new_high)],
+            // Levels without a readable number give no hint about direction
+            _ => match (extract_number(old_str), extract_number(new_str)) {
+                (Some(old_value), Some(new_value)) => vec![(old_value, new_value)],
+                _ => continue
+            }
+        };
+        for (old_value, new_value) in comparisons {
+            if new_value < old_value && !is_dec {
+                is_dec = true
+            }
+            if new_value > old_value && !is_inc {
+                is_inc = true
+            }
+        }
+    }
+
+    if is_inc && is_dec {
+        return "rescaled".to_string()
+    }
+    if is_inc {
+        return "increased".to_string()
+    }
+    if is_dec {
+        return "decreased".to_string()
+    }
+
+    "changed".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use crate::model::{ChangeData, DiffSummary, PatchChange, ParseReport, TextOptions, GroupBy, NO_CHANGES_EXIT_CODE, distinct_properties, entity_verdicts, regroup, filter_by_magnitude, no_changes_exit, OutputOrder, Verdict, classify_change, drop_unchanged_values, exclude_changes, only_additions, sort_for_output, strip_other, talent_level, absolute_change_direction, extract_number, infer_unit, parse_numeric_value, uniform_value};
+
+    #[test]
+    fn range_values_work() {
+        assert_eq!("increased".to_string(), absolute_change_direction("200-300", "250-350"));
+        assert_eq!("rescaled".to_string(), absolute_change_direction("200-300", "150-400"));
+        assert_eq!("decreased".to_string(), absolute_change_direction("200-300", "240"));
+        assert_eq!(Some(250.0), extract_number("200-300"));
+
+        let result = PatchChange::parse_text("Damage increased from 200-300 to 250-350", "Heroes > Lina".to_string(), "7.32");
+        assert_eq!("Damage increased from 200-300 to 250-350".to_string(), result.write_text_line())
+    }
+
+    #[test]
+    fn abs_change_inc_works() {
+        let old = "1s".to_string();
+        let new = "2s".to_string();
+        assert_eq!("increased".to_string(), absolute_change_direction(&old, &new));
+    }
+
+    #[test]
+    fn abs_change_dec_works() {
+        let old = "2s".to_string();
+        let new = "1s".to_string();
+        assert_eq!("decreased".to_string(), absolute_change_direction(&old, &new));
+    }
+
+    #[test]
+    fn abs_change_res_works() {
+        let old = "1/3/4/6s".to_string();
+        let new = "2/3/4/5s".to_string();
+        assert_eq!("rescaled".to_string(), absolute_change_direction(&old, &new));
+    }
+
+    #[test]
+    fn leading_dot_number_works() {
+        assert_eq!(Some(0.5), extract_number(".5s"));
+        assert_eq!("increased".to_string(), absolute_change_direction(".5s", "1s"));
+    }
+
+    #[test]
+    fn negative_number_works() {
+        assert_eq!(Some(-1.0), extract_number("-1"));
+        assert_eq!("increased".to_string(), absolute_change_direction("-1", "2"));
+        assert_eq!("increased".to_string(), absolute_change_direction("-5", "-3"));
+        assert_eq!(
+            "Heroes > Zeus > Base armor increased from -1 to 2".to_string(),
+            PatchChange::parse_text("Base armor increased from -1 to 2", "Heroes > Zeus", "7.32").write_text()
+        )
+    }
+
+    #[test]
+    fn parse_numeric_value_works() {
+        assert_eq!(Some(800.0), parse_numeric_value("800"));
+        assert_eq!(Some(0.5), parse_numeric_value(".5"));
+        assert_eq!(Some(1000.0), parse_numeric_value("1,000"));
+        assert_eq!(Some(1250000.5), parse_numeric_value("1,250,000.5"));
+        assert_eq!(Some(-15.0), parse_numeric_value("-15"));
+        assert_eq!(Some(-1500.0), parse_numeric_value("-1,500"));
+        assert_eq!(Some(25.0), parse_numeric_value("25%"));
+        assert_eq!(Some(-0.5), parse_numeric_value("-0.5%"));
+        assert_eq!(Some(1000.0), parse_numeric_value("1,000 gold"));
+        assert_eq!(Some(800.0), parse_numeric_value("800gold"));
+        assert_eq!(Some(2.5), parse_numeric_value("2.5s"));
+        assert_eq!(Some(150.0), parse_numeric_value("150 HP"));
+        assert_eq!(Some(75.0), parse_numeric_value("75 MP"));
+        assert_eq!(Some(10.0), parse_numeric_value(" 10 "))
+    }
+
+    #[test]
+    fn parse_numeric_value_rejects_other_text() {
+        assert_eq!(None, parse_numeric_value(""));
+        assert_eq!(None, parse_numeric_value("gold"));
+        assert_eq!(None, parse_numeric_value("1,00"));
+        assert_eq!(None, parse_numeric_value("1,0000"));
+        assert_eq!(None, parse_numeric_value("+15 Damage"));
+        assert_eq!(None, parse_numeric_value("10 armor"));
+        assert_eq!(None, parse_numeric_value("1/2"));
+        assert_eq!(None, parse_numeric_value("inf"));
+        assert_eq!(None, parse_numeric_value("1e5"))
+    }
+
+    #[test]
+    fn thousands_separator_cost_works() {
+        assert_eq!(Some(1000.0), extract_number("1,000"));
+        let result = PatchChange::parse_text("Recipe cost reduced from 1,000 to 800 gold", "Items > Blink Dagger", "7.32");
+        assert_eq!(ChangeData::AbsoluteChange("1,000".to_string(), "800".to_string()), result.data);
+        assert_eq!("Items > Blink Dagger > Recipe cost decreased from 1,000 to 800".to_string(), result.write_text())
+    }
+
+    #[test]
+    fn spaced_slash_is_fraction() {
+        assert_eq!(Some(0.5), extract_number("1 / 2"));
+        assert_eq!("decreased".to_string(), absolute_change_direction("1 / 2", "1 / 4"));
+    }
+
+    #[test]
+    fn non_numeric_value_is_changed() {
+        assert_eq!("changed".to_string(), absolute_change_direction("Lvl.", "none"));
+    }
+
+    #[test]
+    fn classify_change_works() {
+        let version = "7.32".to_string();
+        let buff = PatchChange::new(
+            "Items > Blade Mail > Duration",
+            &version,
+            ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string()));
+        let cooldown_buff = PatchChange::new(
+            "Heroes > Zeus > Thundergod's Wrath > Cooldown",
+            &version,
+            ChangeData::AbsoluteChange("90".to_string(), "80".to_string()));
+        let nerf = PatchChange::new("Heroes > Zeus > Base armor", &version, ChangeData::RelativeChange(-1));
+        let neutral = PatchChange::new("Heroes > Zeus", &version, ChangeData::OtherChange("Random Change".to_string()));
+
+        assert_eq!(Verdict::Buff, classify_change(&buff));
+        assert_eq!(Verdict::Buff, classify_change(&cooldown_buff));
+        assert_eq!(Verdict::Nerf, classify_change(&nerf));
+        assert_eq!(Verdict::Neutral, classify_change(&neutral))
+    }
+
+    #[test]
+    fn only_additions_works() {
+        let changes = vec![
+            PatchChange::parse_text("Now has a 50 Mana Cost", "Heroes > Zeus > Arc Lightning", "7.32"),
+            PatchChange::parse_text("Armor increased from 4 to 5", "Items > Blade Mail", "7.32"),
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32")
+        ];
+        let result: Vec<String> = only_additions(changes).iter().map(|change| change.write_text()).collect();
+
+        assert_eq!(vec!["Heroes > Zeus > Arc Lightning > Mana Cost increased from 0 to 50".to_string()], result)
+    }
+
+    #[test]
+    fn drop_unchanged_values_keeps_text() {
+        let change = PatchChange::parse_text("Level 10 Talent +15 Movement Speed replaced with +15% Slow", "Heroes > Abaddon", "7.32");
+
+        assert_eq!(1, drop_unchanged_values(vec![change], 0.01).len())
+    }
+
+    #[test]
+    fn uniform_value_works() {
+        assert_eq!(Some("100"), uniform_value("100/100/100/100"));
+        assert_eq!(None, uniform_value("100/110/120/130"));
+        assert_eq!(None, uniform_value("100"))
+    }
+
+    #[test]
+    fn sort_for_output_priority_works() {
+        let version = "7.32".to_string();
+        let mut changes = vec![
+            PatchChange::new("Heroes > Zeus > Talent > Level 10 Talent", &version,
+                ChangeData::AbsoluteChange("+1 Armor".to_string(), "+2 Armor".to_string())),
+            PatchChange::new("Heroes > Zeus > Arc Lightning > Cooldown", &version,
+                ChangeData::AbsoluteChange("2".to_string(), "1.6".to_string())),
+            PatchChange::new("Heroes > Zeus > Base armor", &version, ChangeData::RelativeChange(1)),
+            PatchChange::new("Heroes > Axe > Vision", &version, ChangeData::RelativeChange(100))
+        ];
+        let order = OutputOrder { first: vec!["Base".to_string()], last: vec!["Talent".to_string()] };
+        sort_for_output(&mut changes, &order);
+
+        let properties: Vec<String> = changes.iter().map(|change| change.property.join()).collect();
+        assert_eq!(vec![
+            "Heroes > Axe > Vision",
+            "Heroes > Zeus > Base armor",
+            "Heroes > Zeus > Arc Lightning > Cooldown",
+            "Heroes > Zeus > Talent > Level 10 Talent"
+        ], properties)
+    }
+
+    #[test]
+    fn infer_unit_works() {
+        assert_eq!(Some("s".to_string()), infer_unit("Heroes > Zeus > Base attack time", "1.7"));
+        assert_eq!(None, infer_unit("Heroes > Zeus > Attack range", "600"));
+        assert_eq!(Some("%".to_string()), infer_unit("Items > Desolator > Chance", "15%"));
+        assert_eq!(None, infer_unit("Heroes > Zeus > Base attack time", "fast"))
+    }
+
+    #[test]
+    fn talents_sort_by_level() {
+        let version = "7.32".to_string();
+        let mut changes = vec![
+            PatchChange::new("Heroes > Zeus > Talent > Level 25 Talent", &version,
+                ChangeData::AbsoluteChange("+1 Armor".to_string(), "+2 Armor".to_string())),
+            PatchChange::new("Heroes > Zeus > Talent > Level 8 Talent", &version,
+                ChangeData::AbsoluteChange("+1 Armor".to_string(), "+2 Armor".to_string())),
+            PatchChange::new("Heroes > Zeus > Talent > Level 10 Talent", &version,
+                ChangeData::AbsoluteChange("+1 Armor".to_string(), "+2 Armor".to_string()))
+        ];
+        sort_for_output(&mut changes, &OutputOrder::default());
+
+        let levels: Vec<Option<u32>> = changes.iter().map(|change| talent_level(&change.property.join())).collect();
+        assert_eq!(vec![Some(8), Some(10), Some(25)], levels);
+        assert_eq!(None, talent_level("Heroes > Zeus > Base armor"))
+    }
+
+    #[test]
+    fn parse_report_threshold_works() {
+        let tree_location = "Heroes > Zeus".to_string();
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Something random", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Cooldown decreased from 90 to 80", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Something else", tree_location, "7.32")
+        ];
+        let report = ParseReport::from_changes(&changes);
+
+        assert_eq!(ParseReport { total: 4, unparsed: 2 }, report);
+        assert!(report.passes(50.0));
+        assert!(!report.passes(25.0));
+        assert!(ParseReport::from_changes(&[]).passes(0.0))
+    }
+
+    #[test]
+    fn diff_summary_works() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Cooldown decreased from 90 to 80", "Heroes > Zeus > Arc Lightning", "7.32"),
+            PatchChange::parse_text("Damage decreased from 100 to 90", "Heroes > Zeus > Arc Lightning", "7.32"),
+            PatchChange::parse_text("Something random", "Heroes > Zeus", "7.32")
+        ];
+
+        assert_eq!(DiffSummary { total: 4, unparsed: 1, buffs: 2, nerfs: 1 }, DiffSummary::from_changes(&changes))
+    }
+
+    #[test]
+    fn entity_verdicts_works() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Cooldown decreased from 90 to 80", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Base damage decreased by 2", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Something random", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Lina", "7.32"),
+            PatchChange::parse_text("Base damage decreased by 1", "Heroes > Lina", "7.32"),
+            PatchChange::parse_text("Base armor decreased by 1", "Heroes > Axe", "7.32"),
+            PatchChange::parse_text("Something else", "Items > Blade Mail", "7.32")
+        ];
+
+        assert_eq!(vec![
+            ("Axe".to_string(), Verdict::Nerf, 0, 1),
+            ("Lina".to_string(), Verdict::Mixed, 1, 1),
+            ("Zeus".to_string(), Verdict::Buff, 2, 1),
+            ("Blade Mail".to_string(), Verdict::Neutral, 0, 0)
+        ], entity_verdicts(&changes))
+    }
+
+    #[test]
+    fn verdict_regroup_works() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Cooldown increased from 80 to 90", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Something random", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Duration increased from 4.5s to 5.5s", "Items > Blade Mail", "7.32")
+        ];
+        let groups: Vec<(String, Vec<String>)> = regroup(changes, GroupBy::Verdict).into_iter()
+            .map(|(name, group)| (name, group.iter().map(|change| change.write_text()).collect()))
+            .collect();
+
+        assert_eq!(vec![
+            ("Buffs".to_string(), vec![
+                "Heroes > Zeus > Base armor increased by 1".to_string(),
+                "Items > Blade Mail > Duration increased from 4.5s to 5.5s".to_string()
+            ]),
+            ("Nerfs".to_string(), vec!["Heroes > Zeus > Cooldown increased from 80 to 90".to_string()]),
+            ("Other".to_string(), vec!["Heroes > Zeus > Something random".to_string()])
+        ], groups)
+    }
+
+    #[test]
+    fn section_regroup_works() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Lina", "7.32"),
+            PatchChange::parse_text("Duration increased from 4.5s to 5.5s", "Items > Blade Mail", "7.32")
+        ];
+        let names: Vec<(String, usize)> = regroup(changes.clone(), GroupBy::Section).into_iter().map(|(name, group)| (name, group.len())).collect();
+        let entities: Vec<String> = regroup(changes, GroupBy::Entity).into_iter().map(|(name, _)| name).collect();
+
+        assert_eq!(vec![("Heroes".to_string(), 2), ("Items".to_string(), 1)], names);
+        assert_eq!(vec!["Heroes > Zeus".to_string(), "Heroes > Lina".to_string(), "Items > Blade Mail".to_string()], entities)
+    }
+
+    #[test]
+    fn distinct_properties_works() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Base armor increased by 2", "Heroes > Zeus", "7.32b"),
+            PatchChange::parse_text("Duration increased from 4.5s to 5.5s", "Items > Blade Mail", "7.32"),
+            PatchChange::parse_text("Something random", "Heroes > Zeus", "7.32")
+        ];
+
+        assert_eq!(BTreeSet::from([
+            "Heroes > Zeus".to_string(),
+            "Heroes > Zeus > Base armor".to_string(),
+            "Items > Blade Mail > Duration".to_string()
+        ]), distinct_properties(&changes))
+    }
+
+    #[test]
+    fn filter_by_magnitude_works() {
+        let tree_location = "Heroes > Zeus".to_string();
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Base damage increased by 10", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Movement speed increased from 300 to 302", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Cast point decreased from 0.3 to 0.2", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Mana cost decreased from 100/110/120 to 99/109/119", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Something random", tree_location, "7.32")
+        ];
+        let result: Vec<String> = filter_by_magnitude(changes, 5.0).iter().map(|change| change.write_text_line()).collect();
+
+        assert_eq!(vec![
+            "Base damage increased by 10".to_string(),
+            "Cast point decreased from 0.3 to 0.2".to_string(),
+            "Mana cost decreased from 100/110/120 to 99/109/119".to_string(),
+            "Something random".to_string()
+        ], result)
+    }
+
+    #[test]
+    fn no_changes_exit_works() {
+        let reverted = vec![PatchChange::new("Heroes > Zeus > Base armor", "7.32b", ChangeData::RelativeChange(0))];
+        let changed = vec![PatchChange::new("Heroes > Zeus > Base armor", "7.32b", ChangeData::RelativeChange(1))];
+        let expected = Some((NO_CHANGES_EXIT_CODE, "No changes between 7.32 and 7.32b".to_string()));
+
+        assert_eq!(expected, no_changes_exit(&[], &TextOptions::default(), "7.32", "7.32b"));
+        assert_eq!(expected, no_changes_exit(&reverted, &TextOptions::default(), "7.32", "7.32b"));
+        assert_eq!(None, no_changes_exit(&reverted, &TextOptions { show_reverted: true, ..Default::default() }, "7.32", "7.32b"));
+        assert_eq!(None, no_changes_exit(&changed, &TextOptions::default(), "7.32", "7.32b"))
+    }
+
+    #[test]
+    fn strip_other_works() {
+        let tree_location = "Heroes > Zeus".to_string();
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Something random", tree_location.clone(), "7.32"),
+            PatchChange::parse_text("Cooldown decreased from 90 to 80", tree_location, "7.32")
+        ];
+        let result = strip_other(changes);
+
+        let properties: Vec<String> = result.iter().map(|change| change.property.join()).collect();
+        assert_eq!(vec!["Heroes > Zeus > Base armor", "Heroes > Zeus > Cooldown"], properties)
+    }
+
+    #[test]
+    fn exclude_changes_works() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus".to_string(), "7.32"),
+            PatchChange::parse_text("Bounty increased by 5", "General > Creeps".to_string(), "7.32"),
+            PatchChange::parse_text("Cooldown decreased from 90 to 80", "Heroes > Zeus > Thundergod's Wrath".to_string(), "7.32"),
+            PatchChange::parse_text("Armor increased from 4 to 5", "Items > Blade Mail".to_string(), "7.32")
+        ];
+        let patterns = vec!["^General".to_string(), "Thundergod's Wrath".to_string()];
+        let result = exclude_changes(changes, &patterns);
+
+        let properties: Vec<String> = result.iter().map(|change| change.property.join()).collect();
+        assert_eq!(vec!["Heroes > Zeus > Base armor", "Items > Blade Mail > Armor"], properties)
+    }
+}
@@ -0,0 +1,608 @@
+use log::info;
+use regex::Regex;
+
+use super::{ALL_LEVELS, UNKNOWN_VALUE, DECIMAL_SCALE, ChangeData, PatchChange, TreePath};
+
+// Values may carry an attack type, like "from 5 (melee) to 6 (melee)", or be a spaced fraction like "1 / 2"
+pub const ABS_NUM_STR: &str = r"(.*) (?:increased|decreased|reduced) from (\S*(?: / \S+)*(?: \((?:melee|ranged)\))?) to (\S*(?: / \S+)*(?: \((?:melee|ranged)\))?)";
+pub const REL_NUM_STR: &str = r"(.*) (increased|decreased) by (\S*$)";
+pub const ABS_TXT_STR: &str = r"(.*Talent) (.*) replaced with (.*)";
+pub const CURRENCY_STR: &str = r"(.*) (?:increased|decreased|reduced) from (\d[\d,]*) ?(?:gold )?to (\d[\d,]*) ?gold";
+pub const TARGET_NUM_STR: &str = r"(.*) (?:increased|decreased|reduced) to (\S*)$";
+pub const NEW_NUM_STR: &str = r"(?:^|.* )[Nn]ow has a (\d*\.?\d+\S*) ([^,]*)";
+// "Now grants 10 Armor" or "Provides 25 Attack Speed", where the stat has to be capitalized words so prose is left alone
+pub const GRANTS_STR: &str = r"^(?:[Nn]ow )?(?:[Gg]rants|[Pp]rovides) \+?(\d*\.?\d+%?) ([A-Z][\w']*(?: [A-Z][\w']*){0,3})\.?$";
+// "Now stuns for 1.5 seconds", a duration given without its old value, or "Roots for an additional 0.5 seconds"
+pub const DISABLE_DURATION_STR: &str = r"(?i)^(?:now )?(stuns|roots|slows) for (an additional )?(\d*\.?\d+)(?: ?seconds?|s)?\.?$";
+// "Level 10 Talent Fissure Damage increased from +80 to +90", the talent is a group above the property
+pub const TALENT_NUM_STR: &str = r"^(.*Level \d+ Talent) (.+?) (?:increased|decreased|reduced) from (\S*) to (\S*)$";
+// Shard price changes are listed under General or under the item depending on the patch
+pub const SHARD_COST_STR: &str = r"(?i)^Aghanim.s Shard (?:gold )?cost (?:increased|decreased|reduced) from (\d[\d,]*) ?(?:gold )?to (\d[\d,]*)";
+const SHARD_COST_PROPERTY: &str = "Items > Aghanim's Shard > Cost";
+pub const TALENT_REMOVED_STR: &str = r"^(.*Level \d+ Talent) (.+) removed\.?$";
+pub const CAN_STR: &str = r"^[Cc]an (now|no longer) (.+?)\.?$";
+// "Bonus damage per stack" -> ("Bonus damage", "per stack")
+const QUALIFIER_STR: &str = r"^(.+?) (per .+)$";
+// Rates are a different stat from the property they qualify, so they stay in the property name
+const RATE_QUALIFIERS: [&str; 2] = ["per second", "per minute"];
+// Property keywords for charge mechanics, which are grouped under CHARGE_GROUP
+const CHARGE_KEYWORDS: [&str; 3] = ["charges", "charge restore", "per charge"];
+const CHARGE_GROUP: &str = "Charges";
+// Per-level attribute gains are grouped under ATTRIBUTE_GAIN_GROUP
+const ATTRIBUTE_GAINS: [&str; 3] = ["strength gain", "agility gain", "intelligence gain"];
+const ATTRIBUTE_GAIN_GROUP: &str = "Attribute Gain";
+// The duration property of each disable DISABLE_DURATION_STR matches
+const DISABLE_DURATIONS: [(&str, &str); 3] = [("stuns", "Stun Duration"), ("roots", "Root Duration"), ("slows", "Slow Duration")];
+
+impl PatchChange {
+    pub fn parse_text(change_line: &str, tree_location: impl Into<TreePath>, version: &str) -> PatchChange {
+        let tree_location = tree_location.into();
+        // "Damage increased from 100 to 120 at all levels" is a scalar standing in for every level,
+        // so it is parsed without the suffix and tagged instead
+        if let Some(line) = change_line.strip_suffix(ALL_LEVELS).and_then(|line| line.strip_suffix(' ')) {
+            let mut change = PatchChange::parse_line(line, tree_location.clone(), version);
+            if !matches!(change.data, ChangeData::OtherChange(_)) {
+                change.all_levels = true;
+                return change
+            }
+        }
+        PatchChange::parse_line(change_line, tree_location, version)
+    }
+
+    fn parse_line(change_line: &str, tree_location: TreePath, version: &str) -> PatchChange {
+        let abs_num_change = Regex::new(ABS_NUM_STR).unwrap();
+        let rel_num_change = Regex::new(REL_NUM_STR).unwrap();
+        let abs_txt_change = Regex::new(ABS_TXT_STR).unwrap();
+        let new_num_change = Regex::new(NEW_NUM_STR).unwrap();
+        let currency_change = Regex::new(CURRENCY_STR).unwrap();
+        let target_num_change = Regex::new(TARGET_NUM_STR).unwrap();
+        let grants_change = Regex::new(GRANTS_STR).unwrap();
+        let disable_duration = Regex::new(DISABLE_DURATION_STR).unwrap();
+        let can_change = Regex::new(CAN_STR).unwrap();
+        let talent_removed = Regex::new(TALENT_REMOVED_STR).unwrap();
+        let talent_num_change = Regex::new(TALENT_NUM_STR).unwrap();
+        let shard_cost_change = Regex::new(SHARD_COST_STR).unwrap();
+
+        if let Some(capture_groups) = shard_cost_change.captures(change_line) {
+            let data = ChangeData::AbsoluteChange(capture_groups[1].to_string(), capture_groups[2].to_string());
+            PatchChange::at(TreePath::from(SHARD_COST_PROPERTY), version, data)
+
+        // Talent lines are matched first, so a numeric talent change keeps its level as a group
+        } else if talent_num_change.is_match(change_line) {
+            let capture_groups = talent_num_change.captures(change_line).unwrap();
+            let mut talent_location = tree_location;
+            talent_location.push(capture_groups.get(1).unwrap().as_str());
+            let (property, qualifier) = property_path(talent_location, capture_groups.get(2).unwrap().as_str());
+            let data = ChangeData::AbsoluteChange(
+                capture_groups.get(3).unwrap().as_str().to_string(),
+                capture_groups.get(4).unwrap().as_str().to_string()
+            );
+            PatchChange::at(property, version, data).with_qualifier(qualifier)
+
+        } else if abs_txt_change.is_match(change_line) {
+            let capture_groups = abs_txt_change.captures(change_line).unwrap();
+            let (property, qualifier) = property_path(tree_location, capture_groups.get(1).unwrap().as_str());
+            let data = ChangeData::AbsoluteChange(
+                capture_groups.get(2).unwrap().as_str().to_string(),
+                capture_groups.get(3).unwrap().as_str().to_string()
+            );
+            PatchChange::at(property, version, data).with_qualifier(qualifier)
+
+        } else if talent_removed.is_match(change_line) {
+            let capture_groups = talent_removed.captures(change_line).unwrap();
+            let (property, qualifier) = property_path(tree_location, capture_groups.get(1).unwrap().as_str());
+            let data = ChangeData::RemovedChange(capture_groups.get(2).unwrap().as_str().to_string());
+            PatchChange::at(property, version, data).with_qualifier(qualifier)
+
+        } else if currency_change.is_match(change_line) {
+            let capture_groups = currency_change.captures(change_line).unwrap();
+            let (property, qualifier) = property_path(tree_location, capture_groups.get(1).unwrap().as_str());
+            let data = ChangeData::AbsoluteChange(
+                capture_groups.get(2).unwrap().as_str().to_string(),
+                capture_groups.get(3).unwrap().as_str().to_string()
+            );
+            PatchChange::at(property, version, data).with_qualifier(qualifier)
+
+        } else if abs_num_change.captures(change_line).is_some_and(|capture_groups| {
+            // Both sides need a digit, "from good to better" is not a numeric change
+            has_digit(&capture_groups[2]) && has_digit(&capture_groups[3])
+        }) {
+            let capture_groups = abs_num_change.captures(change_line).unwrap();
+            let (property, qualifier) = property_path(tree_location, capture_groups.get(1).unwrap().as_str());
+            let data = ChangeData::AbsoluteChange(
+                capture_groups.get(2).unwrap().as_str().to_string(),
+                capture_groups.get(3).unwrap().as_str().to_string()
+            );
+            PatchChange::at(property, version, data).with_qualifier(qualifier)
+
+        } else if rel_num_change.is_match(change_line) {
+            let capture_groups = rel_num_change.captures(change_line).unwrap();
+
+            let mut shift_sign = 1;
+            if capture_groups.get(2).unwrap().as_str() == "decreased" {
+                shift_sign = -1;
+            }
+
+            // Decimal amounts are kept in thousandths, anything else like "by 10%" is kept as text
+            let amount = capture_groups.get(3).unwrap().as_str();
+            let data = if let Ok(amount) = amount.parse::<i32>() {
+                ChangeData::RelativeChange(shift_sign * amount)
+            } else if let Some(amount) = parse_thousandths(amount) {
+                ChangeData::RelativeDecimalChange(shift_sign as i64 * amount)
+            } else {
+                info!("Relative amount is not a number under {}: {}", tree_location, change_line);
+                let data = ChangeData::OtherChange(change_line.to_string());
+                return PatchChange::at(tree_location, version, data)
+            };
+
+            let (property, qualifier) = property_path(tree_location, capture_groups.get(1).unwrap().as_str());
+
+            PatchChange::at(property, version, data).with_qualifier(qualifier)
+
+        } else if target_num_change.is_match(change_line) && !change_line.contains("Talent") {
+            let capture_groups = target_num_change.captures(change_line).unwrap();
+            let (property, qualifier) = property_path(tree_location, capture_groups.get(1).unwrap().as_str());
+            let data = ChangeData::AbsoluteChange(
+                UNKNOWN_VALUE.to_string(),
+                capture_groups.get(2).unwrap().as_str().to_string()
+            );
+            PatchChange::at(property, version, data).with_qualifier(qualifier)
+
+        } else if new_num_change.is_match(change_line) {
+            let capture_groups = new_num_change.captures(change_line).unwrap();
+            let (property, qualifier) = property_path(tree_location, capture_groups.get(2).unwrap().as_str());
+            let data = ChangeData::AbsoluteChange(
+                "0".to_string(),
+                capture_groups.get(1).unwrap().as_str().to_string()
+            );
+            PatchChange::at(property, version, data).with_qualifier(qualifier)
+
+        } else if grants_change.is_match(change_line) {
+            let capture_groups = grants_change.captures(change_line).unwrap();
+            let (property, qualifier) = property_path(tree_location, capture_groups.get(2).unwrap().as_str());
+            let data = ChangeData::AbsoluteChange(
+                "0".to_string(),
+                capture_groups.get(1).unwrap().as_str().to_string()
+            );
+            PatchChange::at(property, version, data).with_qualifier(qualifier)
+
+        } else if let Some(capture_groups) = disable_duration.captures(change_line) {
+            let disable = capture_groups[1].to_lowercase();
+            let (_, duration) = DISABLE_DURATIONS.iter().find(|(verb, _)| *verb == disable).unwrap();
+            let amount = &capture_groups[3];
+            let data = if capture_groups.get(2).is_none() {
+                ChangeData::AbsoluteChange(UNKNOWN_VALUE.to_string(), format!("{}s", amount))
+            } else if let Ok(amount) = amount.parse::<i32>() {
+                ChangeData::RelativeChange(amount)
+            } else if let Some(amount) = parse_thousandths(amount) {
+                ChangeData::RelativeDecimalChange(amount)
+            } else {
+                return PatchChange::at(tree_location, version, ChangeData::OtherChange(change_line.to_string()))
+            };
+            let mut property = tree_location;
+            property.push(duration);
+            PatchChange::at(property, version, data)
+
+        } else if can_change.is_match(change_line) {
+            let capture_groups = can_change.captures(change_line).unwrap();
+            let can_now = capture_groups.get(1).unwrap().as_str() == "now";
+            let mut property = tree_location;
+            property.push(&format!("Can {}", capture_groups.get(2).unwrap().as_str()));
+            PatchChange::at(property, version, ChangeData::ToggleChange(!can_now, can_now))
+        } else {
+            info!("No pattern matched under {}: {}", tree_location, change_line);
+            let data = ChangeData::OtherChange(change_line.to_string());
+            PatchChange::at(tree_location, version, data)
+        }
+    }
+}
+
+// "0.15" -> 150, amounts with more than three decimals have no exact thousandths
+fn parse_thousandths(amount: &str) -> Option<i64> {
+    let (whole, fraction) = amount.split_once('.')?;
+    let is_digits = |text: &str| text.chars().all(|c| c.is_ascii_digit());
+    if fraction.is_empty() || fraction.len() > 3 || !is_digits(whole) || !is_digits(fraction) {
+        return None
+    }
+    let whole = if whole.is_empty() { 0 } else { whole.parse::<i64>().ok()? };
+    let fraction = format!("{:0<3}", fraction).parse::<i64>().ok()?;
+    whole.checked_mul(DECIMAL_SCALE)?.checked_add(fraction)
+}
+
+fn has_digit(value: &str) -> bool {
+    value.chars().any(|character| character.is_ascii_digit())
+}
+
+// The path of a property and its "per stack" style qualifier, which is kept out of the path so that
+// "Bonus damage" and "Bonus damage per stack" fold together
+fn property_path(mut tree_location: TreePath, property_name: &str) -> (TreePath, Option<String>) {
+    let lowercase_name = property_name.to_lowercase();
+    if CHARGE_KEYWORDS.iter().any(|keyword| lowercase_name.contains(keyword)) {
+        tree_location.push(CHARGE_GROUP);
+    } else if ATTRIBUTE_GAINS.contains(&lowercase_name.as_str()) {
+        tree_location.push(ATTRIBUTE_GAIN_GROUP);
+    }
+
+    let qualifier_match = Regex::new(QUALIFIER_STR).unwrap();
+    match qualifier_match.captures(property_name) {
+        Some(capture_groups) if !RATE_QUALIFIERS.contains(&capture_groups[2].to_lowercase().as_str()) => {
+            tree_location.push(&capture_groups[1]);
+            (tree_location, Some(capture_groups[2].to_string()))
+        },
+        _ => {
+            tree_location.push(property_name);
+            (tree_location, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{UNKNOWN_VALUE, ChangeData, PatchChange, TextOptions, Verdict, classify_change, patch_diff, talent_level};
+
+    #[test]
+    fn shard_cost_uses_one_property() {
+        for (line, tree_location) in [
+            ("Aghanim's Shard cost increased from 1400 to 1500", "General > Gameplay"),
+            ("Aghanim's Shard gold cost reduced from 1,500 to 1400 gold", "Items > Aghanim's Shard")
+        ] {
+            let result = PatchChange::parse_text(line, tree_location, "7.32");
+            assert_eq!("Items > Aghanim's Shard > Cost", result.property.join());
+        }
+        let result = PatchChange::parse_text("Aghanim's Shard cost increased from 1400 to 1500", "General > Gameplay", "7.32");
+        assert_eq!(ChangeData::AbsoluteChange("1400".to_string(), "1500".to_string()), result.data)
+    }
+
+    #[test]
+    fn talent_num_keeps_talent_group() {
+        let result = PatchChange::parse_text("Level 10 Talent Fissure Damage increased from 10 to 15", "Heroes > Earthshaker > Talent", "7.32");
+        assert_eq!(PatchChange::new(
+            "Heroes > Earthshaker > Talent > Level 10 Talent > Fissure Damage",
+            "7.32",
+            ChangeData::AbsoluteChange("10".to_string(), "15".to_string())
+        ), result);
+        assert_eq!(Some(10), talent_level(&result.property.join()));
+        assert_eq!("Heroes > Earthshaker > Talent > Level 10 Talent > Fissure Damage increased from 10 to 15", result.write_text());
+        assert_eq!(Verdict::Buff, classify_change(&result));
+
+        let written = PatchChange::parse_text(&result.write_text_line(), "Heroes > Earthshaker > Talent > Level 10 Talent", "7.32");
+        assert_eq!(result, written)
+    }
+
+    #[test]
+    fn abs_num_parse_works() {
+        let change_line = "Duration increased from 4.5s to 5.5s";
+        let tree_location = "Items > Blade Mail".to_string();
+        let version = "7.32";
+        let result = PatchChange::parse_text(change_line, tree_location, version);
+        assert_eq!(PatchChange::new(
+            "Items > Blade Mail > Duration",
+            "7.32",
+            ChangeData::AbsoluteChange("4.5s".to_string(), "5.5s".to_string())
+        ), result)
+    }
+
+    #[test]
+    fn spaced_fraction_parse_works() {
+        let result = PatchChange::parse_text("Interval decreased from 1 / 2 to 1 / 3", "Heroes > Zeus > Static Field", "7.32");
+
+        assert_eq!(PatchChange::new(
+            "Heroes > Zeus > Static Field > Interval",
+            "7.32",
+            ChangeData::AbsoluteChange("1 / 2".to_string(), "1 / 3".to_string())
+        ), result);
+        assert_eq!("Heroes > Zeus > Static Field > Interval decreased from 1 / 2 to 1 / 3", result.write_text())
+    }
+
+    #[test]
+    fn rel_num_parse_works() {
+        let change_line = "Base armor increased by 1";
+        let tree_location = "Heroes > Zeus".to_string();
+        let version = "7.32";
+        let result = PatchChange::parse_text(change_line, tree_location, version);
+        assert_eq!(PatchChange::new(
+            "Heroes > Zeus > Base armor",
+            "7.32",
+            ChangeData::RelativeChange(1)
+        ), result)
+    }
+
+    #[test]
+    fn decimal_rel_num_parse_works() {
+        let result = PatchChange::parse_text("Base attack time increased by 0.1", "Heroes > Zeus", "7.32");
+
+        assert_eq!(ChangeData::RelativeDecimalChange(100), result.data);
+        assert_eq!("Heroes > Zeus > Base attack time increased by 0.1".to_string(), result.write_text());
+        assert_eq!(Verdict::Nerf, classify_change(&result))
+    }
+
+    #[test]
+    fn non_integer_rel_num_parse_falls_back() {
+        for change_line in ["Attack speed increased by 10%", "Base armor increased by 1.2345"] {
+            let result = PatchChange::parse_text(change_line, "Heroes > Zeus".to_string(), "7.32");
+            assert_eq!(PatchChange::new(
+                "Heroes > Zeus",
+                "7.32",
+                ChangeData::OtherChange(change_line.to_string())
+            ), result)
+        }
+    }
+
+    #[test]
+    fn abs_txt_parse_works() {
+        let change_line = "Level 10 Talent OP replaced with Why would anyone take this?";
+        let tree_location = "Heroes > Dark Willow > Talent".to_string();
+        let version = "7.32";
+        let result = PatchChange::parse_text(change_line, tree_location, version);
+        assert_eq!(PatchChange::new(
+            "Heroes > Dark Willow > Talent > Level 10 Talent",
+            "7.32",
+            ChangeData::AbsoluteChange("OP".to_string(), "Why would anyone take this?".to_string())
+        ), result)
+    }
+
+    #[test]
+    fn talent_removed_parse_works() {
+        let result = PatchChange::parse_text("Level 15 Talent +300 Health removed", "Heroes > Axe > Talent", "7.32");
+
+        assert_eq!(PatchChange::new(
+            "Heroes > Axe > Talent > Level 15 Talent",
+            "7.32",
+            ChangeData::RemovedChange("+300 Health".to_string())
+        ), result);
+        assert_eq!("Heroes > Axe > Talent > Level 15 Talent +300 Health removed".to_string(), result.write_text());
+        assert_eq!(Some(15), talent_level(&result.property().join()))
+    }
+
+    #[test]
+    fn non_numeric_abs_num_is_other() {
+        let change_line = "Name increased from good to better";
+        let result = PatchChange::parse_text(change_line, "Heroes > Zeus".to_string(), "7.32");
+
+        assert_eq!(ChangeData::OtherChange(change_line.to_string()), result.data)
+    }
+
+    #[test]
+    fn per_qualifier_is_kept_out_of_property() {
+        let change_line = "Bonus damage per stack increased from 5 to 6";
+        let result = PatchChange::parse_text(change_line, "Heroes > Ursa > Fury Swipes".to_string(), "7.32");
+
+        assert_eq!("Heroes > Ursa > Fury Swipes > Bonus damage", result.property.join());
+        assert_eq!(Some("per stack".to_string()), result.qualifier);
+        assert_eq!(change_line.to_string(), result.write_text_line());
+
+        let rate = PatchChange::parse_text("Mana Cost per Second decreased from 16 to 14", "Heroes > Timbersaw".to_string(), "7.32");
+        assert_eq!("Heroes > Timbersaw > Mana Cost per Second", rate.property.join())
+    }
+
+    #[test]
+    fn all_levels_is_tagged() {
+        let change_line = "Damage increased from 100 to 120 at all levels";
+        let result = PatchChange::parse_text(change_line, "Heroes > Zeus > Arc Lightning".to_string(), "7.32");
+
+        assert_eq!("Heroes > Zeus > Arc Lightning > Damage", result.property.join());
+        assert_eq!(ChangeData::AbsoluteChange("100".to_string(), "120".to_string()), result.data);
+        assert!(result.applies_to_all_levels());
+        assert_eq!(change_line.to_string(), result.write_text_line());
+
+        let single = PatchChange::parse_text("Damage increased from 100 to 120", "Heroes > Zeus > Arc Lightning".to_string(), "7.32");
+        assert!(!single.applies_to_all_levels());
+
+        let other = PatchChange::parse_text("Reworked at all levels", "Heroes > Zeus".to_string(), "7.32");
+        assert_eq!(ChangeData::OtherChange("Reworked at all levels".to_string()), other.data);
+        assert!(!other.applies_to_all_levels())
+    }
+
+    #[test]
+    fn attribute_gain_is_grouped() {
+        let result = PatchChange::parse_text("Strength gain increased from 2.8 to 3.0", "Heroes > Axe".to_string(), "7.32");
+
+        assert_eq!("Heroes > Axe > Attribute Gain > Strength gain", result.property.join());
+        assert_eq!(ChangeData::AbsoluteChange("2.8".to_string(), "3.0".to_string()), result.data);
+        assert_eq!("Strength gain increased from 2.8 to 3.0".to_string(), result.write_text_line())
+    }
+
+    #[test]
+    fn charge_property_is_grouped() {
+        let tree_location = "Heroes > Ember Spirit > Fire Remnant".to_string();
+        let charges = PatchChange::parse_text("Max charges increased from 2 to 3", tree_location.clone(), "7.32");
+        let restore = PatchChange::parse_text("Charge restore time reduced from 45 to 40", tree_location.clone(), "7.32");
+        let damage = PatchChange::parse_text("Damage increased from 100 to 120", tree_location, "7.32");
+
+        assert_eq!("Heroes > Ember Spirit > Fire Remnant > Charges > Max charges", charges.property.join());
+        assert_eq!("Heroes > Ember Spirit > Fire Remnant > Charges > Charge restore time", restore.property.join());
+        assert_eq!("Heroes > Ember Spirit > Fire Remnant > Damage", damage.property.join())
+    }
+
+    #[test]
+    fn new_num_needs_now_has_a_number() {
+        let tree_location = "Items > Black King Bar".to_string();
+        let prose = PatchChange::parse_text("This window has a border", tree_location.clone(), "7.32");
+        let word = PatchChange::parse_text("Now has a passive component", tree_location.clone(), "7.32");
+
+        assert_eq!(ChangeData::OtherChange("This window has a border".to_string()), prose.data);
+        assert_eq!(ChangeData::OtherChange("Now has a passive component".to_string()), word.data)
+    }
+
+    #[test]
+    fn grants_parse_works() {
+        let tree_location = "Items > Assault Cuirass".to_string();
+        let grants = PatchChange::parse_text("Now grants 10 Armor", tree_location.clone(), "7.32");
+        let provides = PatchChange::parse_text("Provides 25 Attack Speed", tree_location.clone(), "7.32");
+
+        assert_eq!("Items > Assault Cuirass > Armor", grants.property.join());
+        assert_eq!(ChangeData::AbsoluteChange("0".to_string(), "10".to_string()), grants.data);
+        assert_eq!("Items > Assault Cuirass > Attack Speed", provides.property.join());
+        assert_eq!(ChangeData::AbsoluteChange("0".to_string(), "25".to_string()), provides.data)
+    }
+
+    #[test]
+    fn grants_needs_a_value_and_stat() {
+        let tree_location = "Items > Assault Cuirass".to_string();
+        for line in ["Provides vision over the area", "Now grants 2 charges when picked up", "The aura now grants 10 Armor to allies"] {
+            let result = PatchChange::parse_text(line, tree_location.clone(), "7.32");
+            assert_eq!(ChangeData::OtherChange(line.to_string()), result.data)
+        }
+    }
+
+    #[test]
+    fn disable_duration_parse_works() {
+        let result = PatchChange::parse_text("Now stuns for 1.5 seconds", "Heroes > Zeus > Thundergod's Wrath", "7.32");
+
+        assert_eq!("Heroes > Zeus > Thundergod's Wrath > Stun Duration", result.property.join());
+        assert_eq!(ChangeData::AbsoluteChange(UNKNOWN_VALUE.to_string(), "1.5s".to_string()), result.data);
+        assert_eq!("Stun Duration changed to 1.5s", result.write_text_line());
+
+        let roots = PatchChange::parse_text("Roots for 2s", "Heroes > Batrider > Flaming Lasso", "7.32");
+        assert_eq!("Heroes > Batrider > Flaming Lasso > Root Duration", roots.property.join());
+
+        let additional = PatchChange::parse_text("Roots for an additional 0.5 seconds", "Heroes > Treant Protector > Overgrowth", "7.32");
+        assert_eq!("Heroes > Treant Protector > Overgrowth > Root Duration", additional.property.join());
+        assert_eq!(ChangeData::RelativeDecimalChange(500), additional.data);
+        assert_eq!("Root Duration increased by 0.5", additional.write_text_line());
+
+        let instead = "Dive Bomb now roots for 2.5 seconds instead of stunning for 2s";
+        assert_eq!(ChangeData::OtherChange(instead.to_string()), PatchChange::parse_text(instead, "Heroes > Zeus", "7.32").data)
+    }
+
+    #[test]
+    fn new_num_parse_works() {
+        let change_line = "Avatar now has a 50 Mana Cost";
+        let tree_location = "Items > Black King Bar".to_string();
+        let version = "7.32";
+        let result = PatchChange::parse_text(change_line, tree_location, version);
+        assert_eq!(PatchChange::new(
+            "Items > Black King Bar > Mana Cost",
+            "7.32",
+            ChangeData::AbsoluteChange("0".to_string(), "50".to_string())
+        ), result)
+    }
+
+    #[test]
+    fn currency_parse_works() {
+        let change_line = "Total cost reduced from 4400 to 4100 gold";
+        let tree_location = "Items > Black King Bar".to_string();
+        let version = "7.32";
+        let result = PatchChange::parse_text(change_line, tree_location, version);
+        assert_eq!(PatchChange::new(
+            "Items > Black King Bar > Total cost",
+            "7.32",
+            ChangeData::AbsoluteChange("4400".to_string(), "4100".to_string())
+        ), result)
+    }
+
+    #[test]
+    fn target_num_parse_works() {
+        let change_line = "Cooldown reduced to 10";
+        let tree_location = "Heroes > Zeus > Arc Lightning".to_string();
+        let version = "7.32";
+        let result = PatchChange::parse_text(change_line, tree_location, version);
+        assert_eq!(PatchChange::new(
+            "Heroes > Zeus > Arc Lightning > Cooldown",
+            "7.32",
+            ChangeData::AbsoluteChange(UNKNOWN_VALUE.to_string(), "10".to_string())
+        ), result);
+        assert_eq!("Heroes > Zeus > Arc Lightning > Cooldown changed to 10".to_string(), result.write_text())
+    }
+
+    #[test]
+    fn other_parse_works() {
+        let change_line = "Random Change";
+        let tree_location = "Heroes > Crystal Maiden".to_string();
+        let version = "7.32";
+        let result = PatchChange::parse_text(change_line, tree_location, version);
+        assert_eq!(PatchChange::new(
+            "Heroes > Crystal Maiden",
+            "7.32",
+            ChangeData::OtherChange("Random Change".to_string())
+        ), result)
+    }
+
+    #[test]
+    fn can_now_works() {
+        let result = PatchChange::parse_text("Can now be cast while rooted", "Heroes > Zeus > Arc Lightning", "7.32");
+
+        assert_eq!(
+            "Heroes > Zeus > Arc Lightning > Can be cast while rooted".to_string(),
+            result.property().join()
+        );
+        assert_eq!(ChangeData::ToggleChange(false, true), result.data);
+        assert_eq!("Heroes > Zeus > Arc Lightning > Can now be cast while rooted".to_string(), result.write_text());
+        assert_eq!(Verdict::Buff, classify_change(&result))
+    }
+
+    #[test]
+    fn can_no_longer_works() {
+        let result = PatchChange::parse_text("Can no longer be cast while silenced.", "Heroes > Zeus > Arc Lightning", "7.32");
+
+        assert_eq!(ChangeData::ToggleChange(true, false), result.data);
+        assert_eq!("Heroes > Zeus > Arc Lightning > Can no longer be cast while silenced".to_string(), result.write_text());
+        assert_eq!(Verdict::Nerf, classify_change(&result))
+    }
+
+    #[test]
+    fn numeric_lines_roundtrip() {
+        let tree_location = "Heroes > Zeus".to_string();
+        let changes = vec![
+            PatchChange::new("Heroes > Zeus > Base armor", "7.32", ChangeData::RelativeChange(2)),
+            PatchChange::new("Heroes > Zeus > Base damage", "7.32", ChangeData::RelativeChange(-3)),
+            PatchChange::new("Heroes > Zeus > Cooldown", "7.32",
+                ChangeData::AbsoluteChange("90/80/70".to_string(), "80/70/60".to_string())),
+            PatchChange::new("Heroes > Zeus > Gold cost", "7.32",
+                ChangeData::AbsoluteChange("2000".to_string(), "2100".to_string())),
+            PatchChange::new("Heroes > Zeus > Talent", "7.32",
+                ChangeData::AbsoluteChange("+1 Armor".to_string(), "+2 Armor".to_string()))
+        ];
+
+        for change in changes {
+            let line = change.write_text_line();
+            let result = PatchChange::parse_text(&line, tree_location.clone(), "7.32");
+            assert_eq!(change.data, result.data, "{}", line)
+        }
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn parse_text_never_panics(change_line in "\\PC*") {
+            let change = PatchChange::parse_text(&change_line, "Heroes > Zeus", "7.32");
+            change.write_text();
+            classify_change(&change);
+        }
+
+        #[test]
+        fn parse_text_never_panics_on_change_lines(
+            property in "[A-Za-z ]{0,12}",
+            verb in "(increased|decreased|reduced|replaced with|removed)",
+            old in "[-0-9./%s ]{0,12}",
+            new in "[-0-9./%sa-z ]{0,12}"
+        ) {
+            for change_line in [
+                format!("{} {} from {} to {}", property, verb, old, new),
+                format!("{} {} by {}", property, verb, new),
+                format!("{} {} to {}", property, verb, new),
+                format!("Level 10 Talent {} {} {}", old, verb, new),
+                format!("Now has a {} {}", old, property)
+            ] {
+                let changes = patch_diff(vec![
+                    PatchChange::parse_text(&change_line, "Heroes > Zeus", "7.32"),
+                    PatchChange::parse_text(&change_line, "Heroes > Zeus", "7.32b")
+                ]);
+                for change in changes {
+                    change.write_text();
+                    classify_change(&change);
+                }
+            }
+        }
+
+        #[test]
+        fn relative_amounts_never_panic(amount in 0u64..=(i32::MAX as u64) * 2, decimals in 0u32..1000) {
+            for change_line in [format!("Base armor increased by {}", amount), format!("Base armor decreased by {}.{}", amount, decimals)] {
+                let change = PatchChange::parse_text(&change_line, "Heroes > Zeus", "7.32");
+                change.write_text();
+                classify_change(&change);
+                let changes = patch_diff(vec![change.clone(), PatchChange { version: "7.32b".to_string(), ..change }]);
+                changes[0].write_text_with(&TextOptions { net_delta: true, ..Default::default() });
+            }
+        }
+    }
+}
@@ -0,0 +1,552 @@
+use serde::{Serialize, Serializer};
+use serde_json::json;
+use std::path::Path;
+
+use crate::model::{SEP, ChangeData, DiffReport, GroupBy, PatchChange, TextOptions, Verdict, classify_change, entity_verdicts, regroup, write_signed_thousandths};
+
+const BOLD: &str = "\x1b[1m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+const TEMPLATE_TITLE: &str = "Dota 2 patch diff";
+
+fn paint(text: &str, style: &str, color: bool) -> String {
+    if color && !style.is_empty() {
+        format!("{}{}{}", style, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+// The change as the renderers write it, noting a section only the first patch of the range has.
+// The note is left out of write_text so its lines still parse back into the same change
+fn rendered_line(change: &PatchChange, options: &TextOptions) -> String {
+    let line = change.write_line_with(options);
+    if change.is_old_only_section() { format!("{} (section not in later patches)", line) } else { line }
+}
+
+pub fn render_terminal(changes: &[PatchChange], color: bool, options: &TextOptions) -> String {
+    let mut result = "".to_string();
+
+    let mut current_h2 = "".to_string();
+    let mut current_h3 = "".to_string();
+    let mut current_b = "".to_string();
+
+    for change in changes {
+        // Handling RelativeChange values of 0 (no net change between patches)
+        if change.is_unchanged() && !options.show_reverted {
+            continue
+        }
+        let headers = change.headers();
+        let change_line = rendered_line(change, options);
+
+        if headers[0] != current_h2 {
+            result.push_str(&format!("{}\n", paint(&headers[0], BOLD, color)));
+            current_h2 = headers[0].to_string();
+            current_h3 = "".to_string();
+            current_b = "".to_string();
+        }
+        if headers[1] != current_h3 {
+            result.push_str(&format!("  {}\n", paint(&headers[1], BOLD, color)));
+            current_h3 = headers[1].to_string();
+            current_b = "".to_string();
+        }
+
+        let mut indent = "    ";
+        if headers.len() >= 3 {
+            let group = headers[2..].join(SEP);
+            if group != current_b {
+                result.push_str(&format!("    {}\n", paint(&group, BOLD, color)));
+                current_b = group;
+            }
+            indent = "      ";
+        }
+
+        let style = match classify_change(change) {
+            Verdict::Buff => GREEN,
+            Verdict::Nerf => RED,
+            Verdict::Neutral | Verdict::Mixed => ""
+        };
+        result.push_str(&format!("{}- {}\n", indent, paint(&change_line, style, color)));
+    }
+    result
+}
+
+pub fn render_html(diff_result: &[PatchChange], options: &TextOptions) -> String {
+    if let Some(mode) = options.group_by {
+        return render_grouped_html(diff_result, mode, options)
+    }
+    let mut result = "<div>".to_string();
+
+    let mut current_h2 = "".to_string();
+    let mut current_h3 = "".to_string();
+    let mut current_b = "".to_string();
+
+    for change in diff_result {
+        // Handling RelativeChange values of 0 (no net change between patches)
+        if change.is_unchanged() && !options.show_reverted {
+            continue
+        }
+        let mut headers = change.headers();
+        let mut change_line = rendered_line(change, options);
+        if options.show_version {
+            change_line = format!("{} (since {})", change_line, version_label(change.version()));
+        }
+        // Compact mode writes the group in front of the change instead of nesting a list for it
+        if options.compact && headers.len() > 2 {
+            change_line = format!("{}: {}", headers[2..].join(SEP), change_line);
+            headers = &headers[..2];
+        }
+
+        if headers[0] != current_h2 {
+            if current_h2.is_empty() {
+                result.push_str("<h2>");
+            } else {
+                if !current_b.is_empty() {
+                    result.push_str("</ul></li>")
+                }
+                result.push_str("</ul><h2>")
+            }
+            result.push_str(&headers[0]);
+            result.push_str("</h2>");
+            current_h2 = headers[0].to_string();
+            current_h3 = "".to_string();
+            current_b = "".to_string();
+        }
+        if headers[1] != current_h3 {
+            if current_h3.is_empty() {
+                result.push_str("<h3>");
+            } else {
+                if !current_b.is_empty() {
+                    result.push_str("</ul></li>")
+                }
+                result.push_str("</ul><h3>");
+            }
+            result.push_str(&headers[1]);
+            result.push_str("</h3><ul>");
+            current_h3 = headers[1].to_string();
+            current_b = "".to_string();
+        }
+        // Changes without a group close the list of the group before them
+        let group = headers.get(2..).unwrap_or_default().join(SEP);
+        if group != current_b {
+            if !current_b.is_empty() {
+                result.push_str("</ul></li>");
+            }
+            if !group.is_empty() {
+                result.push_str("<li>");
+                result.push_str(&group);
+                result.push_str("<ul>");
+            }
+            current_b = group;
+        }
+
+        result.push_str(&format!("<li>{}</li>", change_line));
+    }
+
+    if current_h2.is_empty() {
+        result.push_str("<p>No changes</p></div>");
+    } else if current_b.is_empty() {
+        result.push_str("</ul></div>");
+    } else {
+        result.push_str("</ul></li></ul></div>");
+    }
+    result
+}
+
+// One h2 and list per group from regroup, with each change written below the headers its group is named by
+fn render_grouped_html(changes: &[PatchChange], mode: GroupBy, options: &TextOptions) -> String {
+    let shown: Vec<PatchChange> = changes.iter()
+        .filter(|change| options.show_reverted || !change.is_unchanged())
+        .cloned()
+        .collect();
+    if shown.is_empty() {
+        return "<div><p>No changes</p></div>".to_string()
+    }
+    let named_levels = match mode {
+        GroupBy::Section => 1,
+        GroupBy::Entity => 2,
+        GroupBy::Verdict => 0
+    };
+
+    let mut result = "<div>".to_string();
+    for (name, group) in regroup(shown, mode) {
+        result.push_str(&format!("<h2>{}</h2><ul>", name));
+        for change in group {
+            let mut line: Vec<String> = change.headers().iter().skip(named_levels).cloned().collect();
+            line.push(rendered_line(&change, options));
+            let mut change_line = line.join(SEP);
+            if options.show_version {
+                change_line = format!("{} (since {})", change_line, version_label(change.version()));
+            }
+            result.push_str(&format!("<li>{}</li>", change_line));
+        }
+        result.push_str("</ul>");
+    }
+    result.push_str("</div>");
+    result
+}
+
+// Puts content into template at {{content}}, with {{title}} replaced by TEMPLATE_TITLE
+pub fn render_template(template: &str, content: &str) -> String {
+    template.replace("{{title}}", TEMPLATE_TITLE).replace("{{content}}", content)
+}
+
+// Changes from files keep the file path as their version, "./html/7.32c.html" -> "7.32c"
+fn version_label(version: &str) -> &str {
+    Path::new(version).file_name()
+        .and_then(|name| name.to_str())
+        .map_or(version, |name| name.trim_end_matches(".html"))
+}
+
+// Output formats that are written to a file next to the others, named by their extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileFormat {
+    Html,
+    Json,
+    Markdown
+}
+
+impl FileFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FileFormat::Html => "html",
+            FileFormat::Json => "json",
+            FileFormat::Markdown => "md"
+        }
+    }
+}
+
+// Html and markdown only hold the changes, json is the whole report with its range and summary
+pub fn render_file(report: &DiffReport, format: FileFormat, options: &TextOptions) -> String {
+    match format {
+        FileFormat::Html => render_html(&report.changes, options),
+        FileFormat::Json => render_report(report, options),
+        FileFormat::Markdown => render_gfm_table(&report.changes)
+    }
+}
+
+// An array with one object per change, holding its path, version and values
+pub fn render_json(changes: &[PatchChange], options: &TextOptions) -> String {
+    serde_json::to_string_pretty(&shown_changes(changes, options)).unwrap()
+}
+
+// The range, time and counts of the diff around the same entries render_json writes
+pub fn render_report(report: &DiffReport, options: &TextOptions) -> String {
+    let mut document = serde_json::to_value(report).unwrap();
+    document["old"] = json!(version_label(&report.old));
+    document["new"] = json!(version_label(&report.new));
+    document["changes"] = json!(shown_changes(&report.changes, options));
+    serde_json::to_string_pretty(&document).unwrap()
+}
+
+fn shown_changes<'a>(changes: &'a [PatchChange], options: &TextOptions) -> Vec<&'a PatchChange> {
+    changes.iter().filter(|change| options.show_reverted || !change.is_unchanged()).collect()
+}
+
+// One object per change with its path, version and values, the same in render_json and DiffReport
+impl Serialize for PatchChange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut entry = json!({
+            "path": self.property().segments(),
+            "version": version_label(self.version())
+        });
+        match self.data() {
+            ChangeData::AbsoluteChange(old, new) => {
+                entry["old"] = json!(old);
+                entry["new"] = json!(new);
+            },
+            ChangeData::RelativeChange(value) => entry["delta"] = json!(value),
+            ChangeData::RelativeDecimalChange(value) => entry["delta"] = json!(*value as f64 / 1000.0),
+            ChangeData::ToggleChange(old, new) => {
+                entry["old"] = json!(old);
+                entry["new"] = json!(new);
+            },
+            ChangeData::RemovedChange(text) => entry["removed"] = json!(text),
+            ChangeData::OtherChange(text) => entry["text"] = json!(text)
+        }
+        if self.applies_to_all_levels() {
+            entry["all_levels"] = json!(true);
+        }
+        if self.is_old_only_section() {
+            entry["old_only_section"] = json!(true);
+        }
+        entry.serialize(serializer)
+    }
+}
+
+// "1 buff", "2 buffs"
+fn count_of(count: usize, noun: &str) -> String {
+    if count == 1 { format!("1 {}", noun) } else { format!("{} {}s", count, noun) }
+}
+
+// One line per entity like "Zeus: 2 buffs, 1 nerf — net buffed"
+pub fn render_verdicts(changes: &[PatchChange]) -> String {
+    let mut result = "".to_string();
+    for (entity, verdict, buffs, nerfs) in entity_verdicts(changes) {
+        let net = match verdict {
+            Verdict::Buff => "net buffed",
+            Verdict::Nerf => "net nerfed",
+            Verdict::Mixed => "mixed",
+            Verdict::Neutral => "no buffs or nerfs"
+        };
+        result.push_str(&format!("{}: {}, {} — {}\n", entity, count_of(buffs, "buff"), count_of(nerfs, "nerf"), net));
+    }
+    result
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
+
+// One row per change with the values split into columns, for pasting into a changelog
+pub fn render_gfm_table(changes: &[PatchChange]) -> String {
+    let mut result = "| Section | Entity | Property | Old | New | Delta | Note |\n\
+        | --- | --- | --- | --- | --- | --- | --- |\n".to_string();
+
+    for change in changes {
+        if change.is_unchanged() {
+            continue
+        }
+        let segments = change.property().segments();
+        let section = segments.first().map(String::as_str).unwrap_or("");
+        let entity = segments.get(1).map(String::as_str).unwrap_or("");
+        let property = segments.get(2..).unwrap_or_default().join(SEP);
+
+        let (old, new, delta, note) = match change.data() {
+            ChangeData::AbsoluteChange(old, new) => (old.to_string(), new.to_string(), "".to_string(), "".to_string()),
+            ChangeData::RelativeChange(value) => ("".to_string(), "".to_string(), format!("{:+}", value), "".to_string()),
+            ChangeData::RelativeDecimalChange(value) => ("".to_string(), "".to_string(), write_signed_thousandths(*value), "".to_string()),
+            ChangeData::ToggleChange(old, new) => (yes_no(*old).to_string(), yes_no(*new).to_string(), "".to_string(), "".to_string()),
+            ChangeData::RemovedChange(text) => (text.to_string(), "".to_string(), "".to_string(), "removed".to_string()),
+            ChangeData::OtherChange(text) => ("".to_string(), "".to_string(), "".to_string(), text.to_string())
+        };
+        let cells: Vec<String> = [section, entity, &property, &old, &new, &delta, &note].iter()
+            .map(|cell| cell.replace('|', "\\|"))
+            .collect();
+        result.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::document::parse_html_str;
+    use crate::render::{render_gfm_table, render_html, render_json, render_report, version_label, render_template, render_terminal, render_verdicts};
+    use crate::{Config, DiffReport, GroupBy, PatchAccumulator, PatchChange, TextOptions, patch_diff};
+
+    #[test]
+    fn plain_terminal_render_works() {
+        let html = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 1</li>\
+            <li><b>Thundergod's Wrath</b><ul><li>Cooldown decreased from 90 to 80</li></ul></li></ul>\
+            <h2>Items</h2><h3>Blade Mail</h3><ul><li>Duration increased from 4.5s to 5.5s</li></ul></div>";
+        let changes = patch_diff(parse_html_str(html, "7.32", &Config::default()));
+        let result = render_terminal(&changes, false, &TextOptions::default());
+
+        assert_eq!("Heroes\n  Zeus\n    - Base armor increased by 1\n    Thundergod's Wrath\n      - Cooldown decreased from 90 to 80\n\
+            Items\n  Blade Mail\n    - Duration increased from 4.5s to 5.5s\n".to_string(), result)
+    }
+
+    #[test]
+    fn multibyte_text_survives_the_pipeline() {
+        let line = "Mjöllnir — Static Charge now chains to Señor Roshan’s minions";
+        let html = format!("<div class=\"mw-parser-output\"><h2>Items</h2><h3>Mjöllnir</h3>\
+            <ul><li>{}</li><li>Proc damage increased from 150 to 170</li></ul></div>", line);
+        let changes = patch_diff(parse_html_str(&html, "7.32", &Config::default()));
+
+        assert_eq!(vec![format!("Items > Mjöllnir > {}", line), "Items > Mjöllnir > Proc damage increased from 150 to 170".to_string()],
+            changes.iter().map(|change| change.write_text()).collect::<Vec<String>>());
+        assert!(render_html(&changes, &TextOptions::default()).contains(&format!("<li>{}</li>", line)));
+        assert!(render_terminal(&changes, false, &TextOptions::default()).contains(&format!("- {}\n", line)))
+    }
+
+    #[test]
+    fn old_only_section_is_only_noted_when_rendered() {
+        let mut accumulator = PatchAccumulator::new(false);
+        accumulator.add_patch(parse_html_str("<div class=\"mw-parser-output\"><h2>Frostivus</h2><h3>Rewards</h3>\
+            <ul><li>Reward increased from 10 to 20</li></ul></div>", "7.32", &Config::default()));
+        accumulator.add_patch(vec![PatchChange::parse_text("Base armor increased by 1", "Heroes > Lina", "7.32b")]);
+        let changes = accumulator.finish();
+        let note = "Reward increased from 10 to 20 (section not in later patches)";
+
+        assert_eq!("Frostivus > Rewards > Reward increased from 10 to 20", changes[0].write_text());
+        assert!(render_terminal(&changes, false, &TextOptions::default()).contains(note));
+        assert!(render_html(&changes, &TextOptions::default()).contains(note));
+        assert!(render_html(&changes, &TextOptions { group_by: Some(GroupBy::Entity), ..Default::default() }).contains(note))
+    }
+
+    #[test]
+    fn colored_terminal_render_works() {
+        let html = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor decreased by 1</li></ul></div>";
+        let changes = parse_html_str(html, "7.32", &Config::default());
+        let result = render_terminal(&changes, true, &TextOptions::default());
+
+        assert_eq!("\x1b[1mHeroes\x1b[0m\n  \x1b[1mZeus\x1b[0m\n    - \x1b[31mBase armor decreased by 1\x1b[0m\n".to_string(), result)
+    }
+
+    #[test]
+    fn reverted_change_is_only_shown_when_requested() {
+        let html_32 = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 2</li></ul></div>";
+        let html_32b = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor decreased by 2</li></ul></div>";
+        let mut changes = parse_html_str(html_32, "7.32", &Config::default());
+        changes.extend(parse_html_str(html_32b, "7.32b", &Config::default()));
+        let changes = patch_diff(changes);
+
+        assert_eq!("".to_string(), render_terminal(&changes, false, &TextOptions::default()));
+
+        let options = TextOptions { show_reverted: true, ..Default::default() };
+        assert_eq!("Heroes\n  Zeus\n    - Base armor touched (net unchanged)\n".to_string(),
+            render_terminal(&changes, false, &options))
+    }
+
+    #[test]
+    fn other_change_with_separator_keeps_nesting() {
+        let html = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Attack priority changed to Heroes > Creeps</li></ul></div>";
+        let changes = parse_html_str(html, "7.32", &Config::default());
+        let result = render_terminal(&changes, false, &TextOptions::default());
+
+        assert_eq!("Heroes\n  Zeus\n    - Attack priority changed to Heroes > Creeps\n".to_string(), result)
+    }
+
+    #[test]
+    fn gfm_table_works() {
+        let html = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 1</li><li>Lightning now chains | bounces</li>\
+            <li><b>Thundergod's Wrath</b><ul><li>Cooldown decreased from 90 to 80</li></ul></li></ul></div>";
+        let changes = patch_diff(parse_html_str(html, "7.32", &Config::default()));
+        let result = render_gfm_table(&changes);
+
+        assert_eq!("| Section | Entity | Property | Old | New | Delta | Note |\n\
+            | --- | --- | --- | --- | --- | --- | --- |\n\
+            | Heroes | Zeus |  |  |  |  | Lightning now chains \\| bounces |\n\
+            | Heroes | Zeus | Base armor |  |  | +1 |  |\n\
+            | Heroes | Zeus | Thundergod's Wrath > Cooldown | 90 | 80 |  |  |\n".to_string(), result)
+    }
+
+    #[test]
+    fn compact_html_writes_group_inline() {
+        let html = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 2</li><li>Base damage decreased by 2</li>\
+            <li><b>Scepter</b><ul><li>Cooldown decreased from 90 to 80</li></ul></li></ul></div>";
+        let mut changes = parse_html_str(html, "7.32", &Config::default());
+        changes.push(PatchChange::parse_text("Base damage increased by 2", "Heroes > Zeus", "7.32b"));
+        let changes = patch_diff(changes);
+        let options = TextOptions { compact: true, ..Default::default() };
+
+        assert_eq!("<div><h2>Heroes</h2><h3>Zeus</h3><ul><li>Base armor increased by 2</li>\
+            <li>Scepter: Cooldown decreased from 90 to 80</li></ul></div>".to_string(), render_html(&changes, &options));
+        assert_eq!("<div><h2>Heroes</h2><h3>Zeus</h3><ul><li>Base armor increased by 2</li>\
+            <li>Scepter<ul><li>Cooldown decreased from 90 to 80</li></ul></li></ul></div>".to_string(),
+            render_html(&changes, &TextOptions::default()))
+    }
+
+    #[test]
+    fn html_shows_version() {
+        let changes = patch_diff(vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "./html/7.32.html"),
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "./html/7.32b.html")
+        ]);
+        let result = render_html(&changes, &TextOptions { show_version: true, ..Default::default() });
+
+        assert_eq!("<div><h2>Heroes</h2><h3>Zeus</h3><ul><li>Base armor increased by 2 (since 7.32b)</li></ul></div>", result);
+        assert_eq!("7.32", version_label("7.32"))
+    }
+
+    #[test]
+    fn empty_html_has_no_changes() {
+        assert_eq!("<div><p>No changes</p></div>", render_html(&[], &TextOptions::default()));
+
+        let reverted = patch_diff(vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Base armor decreased by 1", "Heroes > Zeus", "7.32b")
+        ]);
+        assert_eq!("<div><p>No changes</p></div>", render_html(&reverted, &TextOptions::default()))
+    }
+
+    #[test]
+    fn html_closes_group_before_ungrouped_change() {
+        let changes = vec![
+            PatchChange::parse_text("Strength gain increased from 2.8 to 3.0", "Heroes > Axe", "7.32"),
+            PatchChange::parse_text("Base damage increased by 3", "Heroes > Axe", "7.32")
+        ];
+
+        assert_eq!("<div><h2>Heroes</h2><h3>Axe</h3><ul><li>Attribute Gain<ul>\
+            <li>Strength gain increased from 2.8 to 3.0</li></ul></li>\
+            <li>Base damage increased by 3</li></ul></div>".to_string(), render_html(&patch_diff(changes), &TextOptions::default()))
+    }
+
+    #[test]
+    fn template_wraps_content() {
+        let changes = vec![PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32")];
+        let template = "<html><head><title>{{title}}</title></head><body>{{content}}</body></html>";
+        let result = render_template(template, &render_html(&changes, &TextOptions::default()));
+
+        assert_eq!("<html><head><title>Dota 2 patch diff</title></head><body><div><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 1</li></ul></div></body></html>".to_string(), result)
+    }
+
+    #[test]
+    fn json_render_works() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Cooldown decreased from 90 to 80", "Heroes > Zeus", "7.32")
+        ];
+        let result: serde_json::Value = serde_json::from_str(&render_json(&changes, &TextOptions::default())).unwrap();
+
+        assert_eq!(serde_json::json!([
+            {"path": ["Heroes", "Zeus", "Base armor"], "version": "7.32", "delta": 1},
+            {"path": ["Heroes", "Zeus", "Cooldown"], "version": "7.32", "old": "90", "new": "80"}
+        ]), result)
+    }
+
+    #[test]
+    fn report_render_works() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32c"),
+            PatchChange::parse_text("Something random", "Heroes > Zeus", "7.32a")
+        ];
+        let report = DiffReport::new("html/7.32.html", "html/7.32c.html", changes);
+        let result: serde_json::Value = serde_json::from_str(&render_report(&report, &TextOptions::default())).unwrap();
+
+        assert_eq!("7.32", result["old"]);
+        assert_eq!("7.32c", result["new"]);
+        assert_eq!(report.timestamp, result["timestamp"]);
+        assert_eq!(serde_json::json!({"total": 2, "unparsed": 1, "buffs": 1, "nerfs": 0}), result["summary"]);
+        assert_eq!(serde_json::json!(["Heroes", "Zeus", "Base armor"]), result["changes"][0]["path"]);
+        assert_eq!(2, result["changes"].as_array().unwrap().len())
+    }
+
+    #[test]
+    fn verdict_grouped_html_works() {
+        let changes = vec![
+            PatchChange::parse_text("Base armor increased by 1", "Heroes > Zeus", "7.32"),
+            PatchChange::parse_text("Cooldown increased from 80 to 90", "Heroes > Zeus > Arc Lightning", "7.32")
+        ];
+        let options = TextOptions { group_by: Some(GroupBy::Verdict), ..Default::default() };
+
+        assert_eq!("<div><h2>Buffs</h2><ul><li>Heroes > Zeus > Base armor increased by 1</li></ul>\
+            <h2>Nerfs</h2><ul><li>Heroes > Zeus > Arc Lightning > Cooldown increased from 80 to 90</li></ul></div>", render_html(&changes, &options));
+        assert_eq!("<div><h2>Heroes > Zeus</h2><ul><li>Base armor increased by 1</li><li>Arc Lightning > Cooldown increased from 80 to 90</li></ul></div>",
+            render_html(&changes, &TextOptions { group_by: Some(GroupBy::Entity), ..Default::default() }))
+    }
+
+    #[test]
+    fn verdicts_render_works() {
+        let html = "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 1</li><li>Cooldown decreased from 90 to 80</li>\
+            <li>Base damage decreased by 2</li><li>Something random</li></ul>\
+            <h3>Lina</h3><ul><li>Base armor increased by 1</li><li>Base damage decreased by 1</li></ul>\
+            <h2>Items</h2><h3>Blade Mail</h3><ul><li>Something else</li></ul></div>";
+        let diff = patch_diff(parse_html_str(html, "7.32", &Config::default()));
+
+        assert_eq!("Lina: 1 buff, 1 nerf — mixed\n\
+            Zeus: 2 buffs, 1 nerf — net buffed\n\
+            Blade Mail: 0 buffs, 0 nerfs — no buffs or nerfs\n", render_verdicts(&diff))
+    }
+}
@@ -0,0 +1,173 @@
+use log::{info, warn};
+use std::io;
+
+use crate::config::Config;
+use crate::files::{get_diff_between, list_versions};
+use crate::model::{DiffReport, TextOptions};
+use crate::render::{FileFormat, render_file};
+use crate::version::expand_version;
+
+// Only this machine can reach the server unless another address is asked for
+pub const DEFAULT_BIND: &str = "127.0.0.1";
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String
+}
+
+impl Response {
+    fn text(status: u16, body: &str) -> Response {
+        Response { status, content_type: "text/plain; charset=utf-8", body: body.to_string() }
+    }
+}
+
+fn content_type(format: FileFormat) -> &'static str {
+    match format {
+        FileFormat::Html => "text/html; charset=utf-8",
+        FileFormat::Json => "application/json",
+        FileFormat::Markdown => "text/markdown; charset=utf-8"
+    }
+}
+
+// Answers "GET /diff?old=7.32&new=7.32c&format=json" with the diff between versions in dirs
+pub fn handle_request(url: &str, dirs: &[&str], config: &Config) -> Response {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    if path != "/diff" {
+        return Response::text(404, "Only /diff is served")
+    }
+
+    let mut old = None;
+    let mut new = None;
+    let mut format = FileFormat::Json;
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        let Some(value) = percent_decode(value) else {
+            return Response::text(400, &format!("Value of {} is not valid percent-encoded UTF-8", key))
+        };
+        let value = value.trim();
+        match key {
+            "old" => old = Some(value.to_string()),
+            "new" => new = Some(value.to_string()),
+            "format" => format = match value {
+                "html" => FileFormat::Html,
+                "json" => FileFormat::Json,
+                "md" => FileFormat::Markdown,
+                _ => return Response::text(400, &format!("Unknown format {}, expected html, json or md", value))
+            },
+            _ => return Response::text(400, &format!("Unknown parameter {}", key))
+        }
+    }
+    let (Some(old), Some(new)) = (old, new) else {
+        return Response::text(400, "Both old and new versions are required")
+    };
+
+    let available = list_versions(dirs);
+    let (old, new) = match (expand_version(&old, &available), expand_version(&new, &available)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(err), _) | (_, Err(err)) => return Response::text(400, &err)
+    };
+    for version in [&old, &new] {
+        if !available.contains(version) {
+            return Response::text(404, &format!("No patch notes for {}", version))
+        }
+    }
+
+    let diff = get_diff_between(dirs, &old, &new, config);
+    Response { status: 200, content_type: content_type(format), body: render_file(&DiffReport::new(&old, &new, diff), format, &TextOptions::default()) }
+}
+
+// "7.32%20" -> "7.32 ", with "+" read as a space. None for a bad escape or bytes that are not UTF-8
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = vec![];
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        match byte {
+            b'%' => {
+                let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+                rest = &tail[2..];
+                continue
+            },
+            b'+' => bytes.push(b' '),
+            _ => bytes.push(byte)
+        }
+        rest = tail;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+pub fn serve(bind: &str, port: u16, dirs: &[&str], config: &Config) -> io::Result<()> {
+    let server = tiny_http::Server::http((bind, port)).map_err(io::Error::other)?;
+    info!("Serving diffs on {}:{}", bind, port);
+    for request in server.incoming_requests() {
+        let response = if *request.method() == tiny_http::Method::Get {
+            handle_request(request.url(), dirs, config)
+        } else {
+            Response::text(405, "Only GET is supported")
+        };
+        let header = tiny_http::Header::from_bytes("Content-Type", response.content_type).unwrap();
+        let reply = tiny_http::Response::from_string(response.body).with_status_code(response.status).with_header(header);
+        if let Err(err) = request.respond(reply) {
+            warn!("Unable to send a response: {}", err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use crate::Config;
+    use crate::server::{handle_request, percent_decode};
+
+    fn version_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("7.32.html"), "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 1</li></ul></div>").unwrap();
+        fs::write(dir.path().join("7.32c.html"), "<div class=\"mw-parser-output\"><h2>Heroes</h2><h3>Zeus</h3>\
+            <ul><li>Base armor increased by 2</li></ul></div>").unwrap();
+        dir
+    }
+
+    #[test]
+    fn diff_request_works() {
+        let dir = version_dir();
+        let response = handle_request("/diff?old=7.32&new=7.32c&format=json", &[dir.path().to_str().unwrap()], &Config::default());
+
+        assert_eq!(200, response.status);
+        assert_eq!("application/json", response.content_type);
+        assert!(response.body.contains("\"delta\": 3"), "{}", response.body)
+    }
+
+    #[test]
+    fn unknown_version_is_not_found() {
+        let dir = version_dir();
+        let response = handle_request("/diff?old=7.32&new=7.33", &[dir.path().to_str().unwrap()], &Config::default());
+
+        assert_eq!(404, response.status)
+    }
+
+    #[test]
+    fn bad_params_are_rejected() {
+        let dir = version_dir();
+        let dir = &[dir.path().to_str().unwrap()];
+
+        assert_eq!(400, handle_request("/diff?old=7.32", dir, &Config::default()).status);
+        assert_eq!(400, handle_request("/diff?old=7.32&new=7.32c&format=pdf", dir, &Config::default()).status);
+        assert_eq!(400, handle_request("/diff?old=latest&new=7.32c", dir, &Config::default()).status);
+        assert_eq!(404, handle_request("/other", dir, &Config::default()).status)
+    }
+
+    #[test]
+    fn query_values_are_decoded() {
+        let dir = version_dir();
+        let dir = &[dir.path().to_str().unwrap()];
+
+        assert_eq!(Some("7.32 c".to_string()), percent_decode("7.32%20c"));
+        assert_eq!(Some("a b".to_string()), percent_decode("a+b"));
+        assert_eq!(None, percent_decode("7.32%2"));
+        assert_eq!(None, percent_decode("%FF"));
+        assert_eq!(200, handle_request("/diff?old=7.32%20&new=7.32%63", dir, &Config::default()).status);
+        assert_eq!(400, handle_request("/diff?old=7.32%zz&new=7.32c", dir, &Config::default()).status)
+    }
+}
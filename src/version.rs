@@ -0,0 +1,201 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+
+pub const DATE_STR: &str = r"^\d{4}-\d{2}-\d{2}$";
+pub const VERSION_STR: &str = r"^(\d+)\.(\d+)([a-z]?)$";
+pub const SHORT_VERSION_STR: &str = r"^\d+[a-z]?$";
+// Used to expand "32c" when no versions are available to infer the major from
+const DEFAULT_MAJOR: u32 = 7;
+
+const BUNDLED_VERSION_DATES: &str = include_str!("../versions.json");
+
+// Orders "7.9" < "7.32" < "7.32b" < "7.32c", which plain string comparison gets wrong
+#[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
+pub struct Version {
+    major: u32,
+    minor: u32,
+    letter: String
+}
+
+impl Version {
+    pub fn parse(text: &str) -> Option<Version> {
+        let version_match = Regex::new(VERSION_STR).unwrap();
+        let capture_groups = version_match.captures(text)?;
+        Some(Version {
+            major: capture_groups.get(1).unwrap().as_str().parse::<u32>().ok()?,
+            minor: capture_groups.get(2).unwrap().as_str().parse::<u32>().ok()?,
+            letter: capture_groups.get(3).unwrap().as_str().to_string()
+        })
+    }
+
+    // Lettered versions released between this one and next, e.g. 7.32a and 7.32b between 7.32 and 7.32c.
+    // Versions of different minors give none since there is no way to know how many letters a minor had
+    pub fn missing_before(&self, next: &Version) -> Vec<String> {
+        if (self.major, self.minor) != (next.major, next.minor) {
+            return vec![]
+        }
+        let first = self.letter.chars().next().map_or(b'a', |letter| letter as u8 + 1);
+        let last = next.letter.chars().next().map_or(b'a', |letter| letter as u8);
+        (first..last).map(|letter| format!("{}.{}{}", self.major, self.minor, letter as char)).collect()
+    }
+}
+
+// Expands "32c" to "7.32c" using the major versions in available, full versions are kept as given
+pub fn expand_version(input: &str, available: &[String]) -> Result<String, String> {
+    if Version::parse(input).is_some() {
+        return Ok(input.to_string())
+    }
+    let short_match = Regex::new(SHORT_VERSION_STR).unwrap();
+    if !short_match.is_match(input) {
+        return Err(format!("{} is not a version like 7.32c or 32c", input))
+    }
+
+    let mut majors: Vec<u32> = available.iter()
+        .filter_map(|version| Version::parse(version))
+        .map(|version| version.major)
+        .collect();
+    majors.sort();
+    majors.dedup();
+    // With several majors, only the ones that have this version are candidates
+    if majors.len() > 1 {
+        majors.retain(|major| available.contains(&format!("{}.{}", major, input)));
+    }
+
+    match majors.as_slice() {
+        [] if available.is_empty() => Ok(format!("{}.{}", DEFAULT_MAJOR, input)),
+        [] => Err(format!("No available version matches {}", input)),
+        [major] => Ok(format!("{}.{}", major, input)),
+        _ => Err(format!("{} is ambiguous between major versions {:?}", input, majors))
+    }
+}
+
+// Release dates are "YYYY-MM-DD" strings, so they order correctly as plain text
+pub struct VersionDates {
+    releases: Vec<(String, String)>
+}
+
+impl VersionDates {
+    pub fn from_json(json: &str) -> Result<VersionDates, String> {
+        let table: BTreeMap<String, String> = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let date_match = Regex::new(DATE_STR).unwrap();
+
+        let mut releases: Vec<(String, String)> = vec![];
+        for (version, date) in table {
+            if !date_match.is_match(&date) {
+                return Err(format!("Release date {} of {} is not YYYY-MM-DD", date, version))
+            }
+            releases.push((date, version));
+        }
+        releases.sort();
+        Ok(VersionDates { releases })
+    }
+
+    pub fn bundled() -> VersionDates {
+        VersionDates::from_json(BUNDLED_VERSION_DATES).expect("Bundled versions.json is invalid")
+    }
+
+    // First version released on or after start and last version released on or before end
+    pub fn resolve(&self, start: &str, end: &str) -> Option<(String, String)> {
+        let (start, end) = if start > end { (end, start) } else { (start, end) };
+        let (_, first) = self.releases.iter().find(|(date, _)| date.as_str() >= start)?;
+        let (_, last) = self.releases.iter().rev().find(|(date, _)| date.as_str() <= end)?;
+
+        if self.release_date(first) > self.release_date(last) {
+            return None
+        }
+        Some((first.to_string(), last.to_string()))
+    }
+
+    fn release_date(&self, version: &str) -> Option<&str> {
+        self.releases.iter().find(|(_, release)| release == version).map(|(date, _)| date.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::{Version, VersionDates, expand_version};
+
+    const TEST_DATES: &str = r#"{"7.32": "2022-08-22", "7.32b": "2022-08-30", "7.32c": "2022-09-27"}"#;
+
+    #[test]
+    fn version_ordering_works() {
+        let mut versions: Vec<Version> = vec!["7.32c", "7.9", "7.32", "7.32b", "6.88"].into_iter()
+            .map(|version| Version::parse(version).unwrap())
+            .collect();
+        versions.sort();
+
+        assert_eq!(vec![
+            Version::parse("6.88").unwrap(),
+            Version::parse("7.9").unwrap(),
+            Version::parse("7.32").unwrap(),
+            Version::parse("7.32b").unwrap(),
+            Version::parse("7.32c").unwrap()
+        ], versions)
+    }
+
+    #[test]
+    fn missing_before_works() {
+        let version = |text| Version::parse(text).unwrap();
+
+        assert_eq!(vec!["7.32a".to_string(), "7.32b".to_string()], version("7.32").missing_before(&version("7.32c")));
+        assert_eq!(vec!["7.32c".to_string()], version("7.32b").missing_before(&version("7.32d")));
+        assert!(version("7.32b").missing_before(&version("7.32c")).is_empty());
+        assert!(version("7.32").missing_before(&version("7.32a")).is_empty());
+        assert!(version("7.32e").missing_before(&version("7.33")).is_empty())
+    }
+
+    #[test]
+    fn expand_version_works() {
+        let available = vec!["7.32".to_string(), "7.32b".to_string(), "7.32c".to_string()];
+
+        assert_eq!(Ok("7.32c".to_string()), expand_version("32c", &available));
+        assert_eq!(Ok("7.32b".to_string()), expand_version("7.32b", &available));
+        assert_eq!(Ok("7.33".to_string()), expand_version("33", &[]));
+        assert!(expand_version("latest", &available).is_err())
+    }
+
+    #[test]
+    fn ambiguous_expand_version_fails() {
+        let available = vec!["6.32c".to_string(), "7.32c".to_string()];
+
+        assert!(expand_version("32c", &available).is_err())
+    }
+
+    #[test]
+    fn invalid_version_fails() {
+        assert_eq!(None, Version::parse("patch_diff"));
+        assert_eq!(None, Version::parse("7.32.1"))
+    }
+
+    #[test]
+    fn resolve_between_patches_works() {
+        let dates = VersionDates::from_json(TEST_DATES).unwrap();
+        assert_eq!(
+            Some(("7.32b".to_string(), "7.32c".to_string())),
+            dates.resolve("2022-08-25", "2022-10-01")
+        );
+        assert_eq!(
+            Some(("7.32".to_string(), "7.32b".to_string())),
+            dates.resolve("2022-08-22", "2022-09-26")
+        )
+    }
+
+    #[test]
+    fn resolve_without_release_fails() {
+        let dates = VersionDates::from_json(TEST_DATES).unwrap();
+        assert_eq!(None, dates.resolve("2022-08-31", "2022-09-26"));
+        assert_eq!(None, dates.resolve("2022-10-01", "2022-12-01"))
+    }
+
+    #[test]
+    fn invalid_date_fails() {
+        let result = VersionDates::from_json(r#"{"7.32": "22/08/2022"}"#);
+        assert!(result.is_err())
+    }
+
+    #[test]
+    fn bundled_dates_load() {
+        let dates = VersionDates::bundled();
+        assert_eq!(Some(("7.32".to_string(), "7.32c".to_string())), dates.resolve("2022-01-01", "2022-12-31"))
+    }
+}